@@ -0,0 +1,27 @@
+use std::time::Instant;
+
+use nikrypt::crypto::{aes::ctr, nonce::CtrNonce};
+
+fn main() {
+    let key = [
+        0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6, 0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f,
+        0x3c,
+    ]
+    .into();
+    let counter = CtrNonce::random();
+    let data = vec![0x41; 64 * 1024 * 1024];
+
+    let start = Instant::now();
+    let sequential = ctr::encrypt(&key, counter, &data).unwrap();
+    println!("sequential: {:?}", start.elapsed());
+
+    let start = Instant::now();
+    let parallel = ctr::encrypt_parallel(&key, counter, &data).unwrap();
+    println!(
+        "parallel on {} threads: {:?}",
+        rayon::current_num_threads(),
+        start.elapsed()
+    );
+
+    assert_eq!(sequential, parallel);
+}
@@ -0,0 +1,231 @@
+use super::{key::Key, Aes, BlockCipher};
+use crate::crypto::{error::CryptoError, nonce::Iv};
+use alloc::vec::Vec;
+
+fn xor_block(a: &mut [u8; 16], b: &[u8]) {
+    for (x, y) in a.iter_mut().zip(b) {
+        *x ^= y;
+    }
+}
+
+/// NIST SP 800-38A Addendum - CBC-CS3, CBC mode with ciphertext stealing
+///
+/// The last block is zero padded, encrypted and the last two ciphertext blocks are swapped. The
+/// second to last block is then truncated, so the ciphertext has the same length as the
+/// plaintext. The last two blocks are swapped even if the length is a multiple of 16, which is
+/// the convention of Kerberos (RFC 3962). `data` has to be longer than one block.
+pub fn encrypt_cts(key: &Key, iv: Iv, data: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    encrypt_cts_with(&Aes::new(key)?, iv, data)
+}
+
+/// Same as `encrypt_cts` for any block cipher
+pub fn encrypt_cts_with<C: BlockCipher + ?Sized>(
+    cipher: &C,
+    iv: Iv,
+    data: &[u8],
+) -> Result<Vec<u8>, CryptoError> {
+    if data.len() <= 16 {
+        return Err(CryptoError::InvalidLength);
+    }
+
+    let mut prev = *iv;
+    let mut out = Vec::with_capacity(data.len());
+    for block in data.chunks(16) {
+        xor_block(&mut prev, block);
+        cipher.encrypt_block(&mut prev);
+        out.extend_from_slice(&prev);
+    }
+
+    // C_1 .. C_{n-2} | C_n | C_{n-1} truncated to the length of P_n
+    let n = out.len() - 16;
+    let d = data.len() - n;
+    let last = out.split_off(n);
+    let second_to_last = out.split_off(n - 16);
+    out.extend_from_slice(&last);
+    out.extend_from_slice(&second_to_last[..d]);
+
+    Ok(out)
+}
+
+/// Inverse of `encrypt_cts`
+pub fn decrypt_cts(key: &Key, iv: Iv, data: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    decrypt_cts_with(&Aes::new(key)?, iv, data)
+}
+
+/// Same as `decrypt_cts` for any block cipher
+pub fn decrypt_cts_with<C: BlockCipher + ?Sized>(
+    cipher: &C,
+    iv: Iv,
+    data: &[u8],
+) -> Result<Vec<u8>, CryptoError> {
+    if data.len() <= 16 {
+        return Err(CryptoError::InvalidLength);
+    }
+
+    // the swapped C_n starts at the last full block boundary before the partial block
+    let n = (data.len() - 1) / 16 * 16 - 16;
+    let d = data.len() - n - 16;
+    let (head, tail) = data.split_at(n);
+
+    let mut out = Vec::with_capacity(data.len());
+    let mut prev = *iv;
+    for block in head.chunks(16) {
+        let mut p: [u8; 16] = block.try_into().unwrap();
+        cipher.decrypt_block(&mut p);
+        xor_block(&mut p, &prev);
+        prev.copy_from_slice(block);
+        out.extend_from_slice(&p);
+    }
+
+    // D = decrypt(C_n) = C_{n-1} ^ (P_n | 0), so the stolen tail of C_{n-1} is D[d..]
+    let mut dn: [u8; 16] = tail[..16].try_into().unwrap();
+    cipher.decrypt_block(&mut dn);
+    let mut cn_1 = dn;
+    cn_1[..d].copy_from_slice(&tail[16..]);
+    xor_block(&mut dn, &cn_1);
+
+    let mut pn_1 = cn_1;
+    cipher.decrypt_block(&mut pn_1);
+    xor_block(&mut pn_1, &prev);
+    out.extend_from_slice(&pn_1);
+    out.extend_from_slice(&dn[..d]);
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::hex;
+
+    #[test]
+    fn rfc3962_test_vectors() {
+        // RFC 3962 - Appendix B - AES 128 with key "chicken teriyaki" and a zero IV
+        let key = Key::from(*b"chicken teriyaki");
+        let iv = Iv::from_bytes([0; 16]);
+        let msg = b"I would like the General Gau's Chicken, please, and wonton soup.";
+
+        for (len, ciphertext) in [
+            (17, "c6353568f2bf8cb4d8a580362da7ff7f97"),
+            (
+                31,
+                "fc00783e0efdb2c1d445d4c8eff7ed2297687268d6ecccc0c07b25e25ecfe5",
+            ),
+            (
+                32,
+                "39312523a78662d5be7fcbcc98ebf5a897687268d6ecccc0c07b25e25ecfe584",
+            ),
+            (
+                47,
+                "97687268d6ecccc0c07b25e25ecfe584b3fffd940c16a18c1b5549d2f838029e\
+                 39312523a78662d5be7fcbcc98ebf5",
+            ),
+            (
+                48,
+                "97687268d6ecccc0c07b25e25ecfe5849dad8bbb96c4cdc03bc103e1a194bbd8\
+                 39312523a78662d5be7fcbcc98ebf5a8",
+            ),
+            (
+                64,
+                "97687268d6ecccc0c07b25e25ecfe58439312523a78662d5be7fcbcc98ebf5a8\
+                 4807efe836ee89a526730dbc2f7bc8409dad8bbb96c4cdc03bc103e1a194bbd8",
+            ),
+        ] {
+            let ciphertext = hex::decode(ciphertext).unwrap();
+            assert_eq!(encrypt_cts(&key, iv, &msg[..len]).unwrap(), ciphertext);
+            assert_eq!(decrypt_cts(&key, iv, &ciphertext).unwrap(), msg[..len]);
+        }
+    }
+
+    /// Toy cipher to drive the mode through `BlockCipher` with something that isn't AES
+    struct XorCipher(u8);
+
+    impl BlockCipher for XorCipher {
+        fn encrypt_block(&self, block: &mut [u8; 16]) {
+            block
+                .iter_mut()
+                .for_each(|b| *b = b.rotate_left(3) ^ self.0);
+        }
+
+        fn decrypt_block(&self, block: &mut [u8; 16]) {
+            block
+                .iter_mut()
+                .for_each(|b| *b = (*b ^ self.0).rotate_right(3));
+        }
+    }
+
+    #[test]
+    fn generic_block_cipher() {
+        let key = Key::from(*b"chicken teriyaki");
+        let iv = Iv::from_bytes([7; 16]);
+        let data: Vec<u8> = (0..100u8).collect();
+
+        assert_eq!(
+            encrypt_cts_with(&Aes::new(&key).unwrap(), iv, &data).unwrap(),
+            encrypt_cts(&key, iv, &data).unwrap()
+        );
+
+        let c = encrypt_cts_with(&XorCipher(0x5a), iv, &data).unwrap();
+        assert_ne!(c, data);
+        assert_eq!(decrypt_cts_with(&XorCipher(0x5a), iv, &c).unwrap(), data);
+    }
+
+    #[test]
+    fn trait_object() {
+        use crate::crypto::{aes::ctr, nonce::CtrNonce};
+        use alloc::boxed::Box;
+
+        let key = Key::from(*b"chicken teriyaki");
+        let iv = Iv::from_bytes([7; 16]);
+        let counter = CtrNonce::from_bytes([9; 16]);
+        let data: Vec<u8> = (0..100u8).collect();
+
+        let ciphers: Vec<Box<dyn BlockCipher>> =
+            vec![Box::new(Aes::new(&key).unwrap()), Box::new(XorCipher(0x5a))];
+        for cipher in &ciphers {
+            let cipher = cipher.as_ref();
+            assert_eq!(cipher.block_size(), 16);
+
+            let c = encrypt_cts_with(cipher, iv, &data).unwrap();
+            assert_eq!(decrypt_cts_with(cipher, iv, &c).unwrap(), data);
+
+            let c = ctr::encrypt_with(cipher, counter, &data);
+            assert_ne!(c, data);
+            assert_eq!(ctr::encrypt_with(cipher, counter, &c), data);
+        }
+
+        // dispatch through the trait object gives the same result as the concrete type
+        assert_eq!(
+            encrypt_cts_with(ciphers[0].as_ref(), iv, &data).unwrap(),
+            encrypt_cts(&key, iv, &data).unwrap()
+        );
+        assert_eq!(
+            ctr::encrypt_with(ciphers[0].as_ref(), counter, &data),
+            ctr::encrypt(&key, counter, &data).unwrap()
+        );
+    }
+
+    #[test]
+    fn round_trip_and_short_input() {
+        let key = Key::from([0x42; 32]);
+        let iv = Iv::random();
+        let data: Vec<u8> = (0..200u8).collect();
+
+        for len in 17..data.len() {
+            let c = encrypt_cts(&key, iv, &data[..len]).unwrap();
+            assert_eq!(c.len(), len);
+            assert_eq!(decrypt_cts(&key, iv, &c).unwrap(), data[..len]);
+        }
+
+        for len in [0, 1, 16] {
+            assert_eq!(
+                encrypt_cts(&key, iv, &data[..len]),
+                Err(CryptoError::InvalidLength)
+            );
+            assert_eq!(
+                decrypt_cts(&key, iv, &data[..len]),
+                Err(CryptoError::InvalidLength)
+            );
+        }
+    }
+}
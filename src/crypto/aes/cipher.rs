@@ -1,6 +1,6 @@
 use super::{state::State, word::Word};
 
-pub fn cipher(in_array: [u8; 16], nr: usize, w: Vec<Word>) -> [u8; 16] {
+pub fn cipher(in_array: [u8; 16], nr: usize, w: &[Word]) -> [u8; 16] {
     /*
         state <-- in                                                          . See Sec. 3.4
         state <-- add_round_key(state, w[0..3])                               . See Sec. 5.1.4
@@ -86,7 +86,7 @@ pub fn cipher(in_array: [u8; 16], nr: usize, w: Vec<Word>) -> [u8; 16] {
     state.get_current_state()
 }
 
-pub fn inv_cipher(in_array: [u8; 16], nr: usize, w: Vec<Word>) -> [u8; 16] {
+pub fn inv_cipher(in_array: [u8; 16], nr: usize, w: &[Word]) -> [u8; 16] {
     /*
        state <-- in                                                             . See Sec. 3.4
        state <-- add_round_key(state, w[4 ∗ Nr..4 ∗ Nr + 3])                   . See Sec. 5.1.4
@@ -130,7 +130,6 @@ pub fn inv_cipher(in_array: [u8; 16], nr: usize, w: Vec<Word>) -> [u8; 16] {
     ]);
 
     for round in (1..nr).rev() {
-        println!("{}", round);
         state.inv_shift_rows();
         state.inv_sub_bytes();
         state.add_round_key([
@@ -195,7 +194,7 @@ mod tests {
             0xD7, 0x3A,
         ];
 
-        assert_eq!(super::cipher(plaintext, 10, w), correct_ciphertext);
+        assert_eq!(super::cipher(plaintext, 10, &w), correct_ciphertext);
     }
 
     #[test]
@@ -216,6 +215,6 @@ mod tests {
             0x77, 0x6F,
         ];
 
-        assert_eq!(super::inv_cipher(ciphertext, 10, w), correct_plaintext);
+        assert_eq!(super::inv_cipher(ciphertext, 10, &w), correct_plaintext);
     }
 }
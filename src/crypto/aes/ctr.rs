@@ -0,0 +1,244 @@
+use super::{key::Key, Aes, BlockCipher};
+use crate::crypto::{error::CryptoError, nonce::CtrNonce};
+use alloc::vec::Vec;
+
+/// Counter block `counter + i`, the whole block is incremented as a 128 bit big endian integer
+fn counter_block(counter: [u8; 16], i: usize) -> [u8; 16] {
+    u128::from_be_bytes(counter)
+        .wrapping_add(i as u128)
+        .to_be_bytes()
+}
+
+/// NIST SP 800-38A - Section 6.5 - The Counter Mode
+///
+/// `C_j = P_j ^ CIPH_K(T_j)` with `T_j = counter + j`. Encryption and decryption are the
+/// same operation.
+pub fn encrypt(key: &Key, counter: CtrNonce, data: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    Ok(encrypt_with(&Aes::new(key)?, counter, data))
+}
+
+/// Same as `encrypt` for any block cipher
+pub fn encrypt_with<C: BlockCipher + ?Sized>(
+    cipher: &C,
+    counter: CtrNonce,
+    data: &[u8],
+) -> Vec<u8> {
+    let counter = *counter;
+
+    let mut out = data.to_vec();
+    for (i, chunk) in out.chunks_mut(16).enumerate() {
+        let mut key_stream = counter_block(counter, i);
+        cipher.encrypt_block(&mut key_stream);
+        for (b, k) in chunk.iter_mut().zip(key_stream) {
+            *b ^= k;
+        }
+    }
+
+    out
+}
+
+/// Same as `encrypt` but the blocks are distributed over the rayon thread pool, since the key
+/// stream blocks don't depend on each other
+#[cfg(feature = "rayon")]
+pub fn encrypt_parallel(key: &Key, counter: CtrNonce, data: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    use rayon::prelude::*;
+
+    let aes = Aes::new(key)?;
+    let counter = *counter;
+
+    let mut out = data.to_vec();
+    out.par_chunks_mut(16).enumerate().for_each(|(i, chunk)| {
+        let mut key_stream = counter_block(counter, i);
+        aes.encrypt_block(&mut key_stream);
+        for (b, k) in chunk.iter_mut().zip(key_stream) {
+            *b ^= k;
+        }
+    });
+
+    Ok(out)
+}
+
+/// Streaming counter mode, every byte written is XORed with the key stream and passed on to the
+/// inner writer. Since encryption and decryption are the same operation this works for both.
+///
+/// The key stream position only advances by the number of bytes the inner writer accepted, so
+/// writes of any size and short writes keep the stream in sync.
+#[cfg(feature = "std")]
+pub struct CtrWriter<W: std::io::Write> {
+    inner: W,
+    aes: Aes,
+    counter: [u8; 16],
+    /// Number of bytes written to the inner writer so far
+    position: usize,
+    /// Index and key stream of the most recently computed block
+    block: (usize, [u8; 16]),
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> CtrWriter<W> {
+    pub fn new(inner: W, key: &Key, counter: CtrNonce) -> Result<Self, CryptoError> {
+        let aes = Aes::new(key)?;
+        let counter = *counter;
+        let mut block = counter_block(counter, 0);
+        aes.encrypt_block(&mut block);
+
+        Ok(Self {
+            inner,
+            aes,
+            counter,
+            position: 0,
+            block: (0, block),
+        })
+    }
+
+    /// Key stream block `i`, reusing the last block if possible
+    fn key_stream(&mut self, i: usize) -> [u8; 16] {
+        if self.block.0 != i {
+            let mut block = counter_block(self.counter, i);
+            self.aes.encrypt_block(&mut block);
+            self.block = (i, block);
+        }
+        self.block.1
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> std::io::Write for CtrWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let mut out = buf.to_vec();
+        for (i, b) in out.iter_mut().enumerate() {
+            let pos = self.position + i;
+            *b ^= self.key_stream(pos / 16)[pos % 16];
+        }
+
+        let n = self.inner.write(&out)?;
+        self.position += n;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::hex;
+
+    fn sp800_38a_key() -> Key {
+        Key::from([
+            0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6, 0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf,
+            0x4f, 0x3c,
+        ])
+    }
+
+    const PLAINTEXT: &str = "6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51\
+                             30c81c46a35ce411e5fbc1191a0a52eff69f2445df4f9b17ad2b417be66c3710";
+
+    #[test]
+    fn sp800_38a_ctr_aes128() {
+        // NIST SP 800-38A - F.5.1 CTR-AES128.Encrypt
+        let counter = CtrNonce::from_bytes(
+            hex::decode("f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff")
+                .unwrap()
+                .try_into()
+                .unwrap(),
+        );
+        let plaintext = hex::decode(PLAINTEXT).unwrap();
+        let ciphertext = hex::decode(
+            "874d6191b620e3261bef6864990db6ce9806f66b7970fdff8617187bb9fffdff\
+             5ae4df3edbd5d35e5b4f09020db03eab1e031dda2fbe03d1792170a0f3009cee",
+        )
+        .unwrap();
+
+        let key = sp800_38a_key();
+        assert_eq!(encrypt(&key, counter, &plaintext).unwrap(), ciphertext);
+        assert_eq!(encrypt(&key, counter, &ciphertext).unwrap(), plaintext);
+    }
+
+    #[test]
+    fn partial_block_and_counter_wrap() {
+        // expected value computed with python's cryptography package
+        let plaintext = hex::decode(PLAINTEXT).unwrap();
+        let ciphertext = hex::decode(
+            "e13338e36cb71962e00d020b4cedbd86d3dae15b04bb352fa0f59febfcb4da3e67da610697ed5aae",
+        )
+        .unwrap();
+
+        let key = sp800_38a_key();
+        assert_eq!(
+            encrypt(&key, CtrNonce::from_bytes([0xff; 16]), &plaintext[..40]).unwrap(),
+            ciphertext
+        );
+        assert!(encrypt(&key, CtrNonce::from_bytes([0; 16]), &[])
+            .unwrap()
+            .is_empty());
+    }
+
+    /// Accepts at most `self.1` bytes per call
+    #[cfg(feature = "std")]
+    struct ShortWriter(Vec<u8>, usize);
+
+    #[cfg(feature = "std")]
+    impl std::io::Write for ShortWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            let n = buf.len().min(self.1);
+            self.0.extend_from_slice(&buf[..n]);
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn writer_round_trip() {
+        use std::io::Write;
+
+        let key = sp800_38a_key();
+        let counter = CtrNonce::from_bytes([0xff; 16]);
+        let data: Vec<u8> = (0..100_000u32).map(|i| (i * 7) as u8).collect();
+        let expected = encrypt(&key, counter, &data).unwrap();
+
+        for (chunk, limit) in [(1, usize::MAX), (7, 5), (16, 16), (1000, 33), (4096, 4096)] {
+            let mut writer = CtrWriter::new(ShortWriter(Vec::new(), limit), &key, counter).unwrap();
+            for c in data.chunks(chunk) {
+                writer.write_all(c).unwrap();
+            }
+            writer.flush().unwrap();
+            let ciphertext = writer.into_inner().0;
+            assert_eq!(ciphertext, expected);
+
+            let mut writer = CtrWriter::new(Vec::new(), &key, counter).unwrap();
+            std::io::copy(&mut ciphertext.as_slice(), &mut writer).unwrap();
+            assert_eq!(writer.into_inner(), data);
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel_matches_sequential() {
+        let key = sp800_38a_key();
+        let data: Vec<u8> = (0..100_000u32).map(|i| (i * 7) as u8).collect();
+
+        for len in [0, 1, 15, 16, 17, 1000, data.len()] {
+            for counter in [[0; 16], [0xff; 16], [0x5a; 16]].map(CtrNonce::from_bytes) {
+                assert_eq!(
+                    encrypt_parallel(&key, counter, &data[..len]).unwrap(),
+                    encrypt(&key, counter, &data[..len]).unwrap()
+                );
+            }
+        }
+    }
+}
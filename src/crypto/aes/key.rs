@@ -1,4 +1,6 @@
 use super::{utils::RCON, word::Word};
+use crate::util::hex;
+use alloc::{string::String, vec, vec::Vec};
 
 #[derive(Debug)]
 pub enum KeyCreationError {
@@ -79,6 +81,16 @@ impl Key {
         }
         Some(w)
     }
+
+    /// Expanded key words `w[0] .. w[4 * nr + 3]` as hex, in the layout of the key expansion
+    /// example of FIPS-197 - Appendix A
+    pub fn expanded_key_hex(&self) -> Vec<String> {
+        self.get_round_keys()
+            .unwrap_or_default()
+            .iter()
+            .map(|w| hex::encode(&w.0))
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -319,4 +331,31 @@ mod tests {
         assert!(w.is_some());
         assert_eq!(correct_round_keys, w.unwrap());
     }
+
+    #[test]
+    fn expanded_key_hex() {
+        // FIPS-197 - Appendix A.1 - Expansion of a 128-bit Cipher Key
+        let key = Key::from([
+            0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6, 0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf,
+            0x4f, 0x3c,
+        ]);
+
+        #[rustfmt::skip]
+        let expected = [
+            "2b7e1516", "28aed2a6", "abf71588", "09cf4f3c",
+            "a0fafe17", "88542cb1", "23a33939", "2a6c7605",
+            "f2c295f2", "7a96b943", "5935807a", "7359f67f",
+            "3d80477d", "4716fe3e", "1e237e44", "6d7a883b",
+            "ef44a541", "a8525b7f", "b671253b", "db0bad00",
+            "d4d1c6f8", "7c839d87", "caf2b8bc", "11f915bc",
+            "6d88a37a", "110b3efd", "dbf98641", "ca0093fd",
+            "4e54f70e", "5f5fc9f3", "84a64fb2", "4ea6dc4f",
+            "ead27321", "b58dbad2", "312bf560", "7f8d292f",
+            "ac7766f3", "19fadc21", "28d12941", "575c006e",
+            "d014f9a8", "c9ee2589", "e13f0cc8", "b6630ca6",
+        ];
+
+        assert_eq!(key.expanded_key_hex(), expected);
+        assert_eq!(Key::from([0; 32]).expanded_key_hex().len(), 60);
+    }
 }
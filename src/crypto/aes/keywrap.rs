@@ -0,0 +1,146 @@
+use super::{
+    cipher::{cipher, inv_cipher},
+    key::Key,
+    key_schedule,
+};
+use crate::crypto::error::CryptoError;
+use crate::util::{ct, zeroize};
+use alloc::vec::Vec;
+
+/// RFC 3394 - Section 2.2.3.1 - Default Initial Value
+const DEFAULT_IV: [u8; 8] = [0xa6; 8];
+
+/// RFC 3394 - Section 2.2.1 - Key Wrap
+///
+/// `key` has to be at least 16 bytes and a multiple of 8 bytes long. The result is 8 bytes
+/// longer than `key`.
+pub fn wrap(kek: Key, key: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    if key.len() < 16 || !key.len().is_multiple_of(8) {
+        return Err(CryptoError::InvalidLength);
+    }
+    let (nr, w) = key_schedule(&kek)?;
+    let n = key.len() / 8;
+
+    let mut a = DEFAULT_IV;
+    let mut r = key.to_vec();
+    let mut b = [0; 16];
+
+    for j in 0..6 {
+        for (i, r_i) in r.chunks_exact_mut(8).enumerate() {
+            b[..8].copy_from_slice(&a);
+            b[8..].copy_from_slice(r_i);
+            b = cipher(b, nr, &w);
+
+            let t = (n * j + i + 1) as u64;
+            a = (u64::from_be_bytes(b[..8].try_into().unwrap()) ^ t).to_be_bytes();
+            r_i.copy_from_slice(&b[8..]);
+        }
+    }
+    zeroize(&mut b);
+
+    let mut out = a.to_vec();
+    out.extend_from_slice(&r);
+    zeroize(&mut r);
+    Ok(out)
+}
+
+/// RFC 3394 - Section 2.2.2 - Key Unwrap
+///
+/// Fails if the integrity check value does not match the default initial value, in that case
+/// none of the unwrapped data is returned
+pub fn unwrap(kek: Key, wrapped: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    if wrapped.len() < 24 || !wrapped.len().is_multiple_of(8) {
+        return Err(CryptoError::InvalidLength);
+    }
+    let (nr, w) = key_schedule(&kek)?;
+    let n = wrapped.len() / 8 - 1;
+
+    let mut a: [u8; 8] = wrapped[..8].try_into().unwrap();
+    let mut r = wrapped[8..].to_vec();
+    let mut b = [0; 16];
+
+    for j in (0..6).rev() {
+        for (i, r_i) in r.chunks_exact_mut(8).enumerate().rev() {
+            let t = (n * j + i + 1) as u64;
+            b[..8].copy_from_slice(&(u64::from_be_bytes(a) ^ t).to_be_bytes());
+            b[8..].copy_from_slice(r_i);
+            b = inv_cipher(b, nr, &w);
+
+            a.copy_from_slice(&b[..8]);
+            r_i.copy_from_slice(&b[8..]);
+        }
+    }
+    zeroize(&mut b);
+
+    if !ct::bytes_eq(&a, &DEFAULT_IV) {
+        zeroize(&mut r);
+        return Err(CryptoError::DecryptionFailed);
+    }
+    Ok(r)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::hex;
+
+    fn kek(len: u8) -> Key {
+        Key::new((0..len).collect()).unwrap()
+    }
+
+    #[test]
+    fn rfc3394_128_bit_kek() {
+        // RFC 3394 - Section 4.1 - Wrap 128 bits of Key Data with a 128-bit KEK
+        let key = hex::decode("00112233445566778899aabbccddeeff").unwrap();
+        let wrapped = hex::decode("1fa68b0a8112b447aef34bd8fb5a7b829d3e862371d2cfe5").unwrap();
+
+        assert_eq!(wrap(kek(16), &key).unwrap(), wrapped);
+        assert_eq!(unwrap(kek(16), &wrapped).unwrap(), key);
+    }
+
+    #[test]
+    fn rfc3394_256_bit_kek() {
+        // RFC 3394 - Section 4.6 - Wrap 256 bits of Key Data with a 256-bit KEK
+        let key = hex::decode("00112233445566778899aabbccddeeff000102030405060708090a0b0c0d0e0f")
+            .unwrap();
+        let wrapped = hex::decode(
+            "28c9f404c4b810f4cbccb35cfb87f8263f5786e2d80ed326cbc7f0e71a99f43bfb988b9b7a02dd21",
+        )
+        .unwrap();
+
+        assert_eq!(wrap(kek(32), &key).unwrap(), wrapped);
+        assert_eq!(unwrap(kek(32), &wrapped).unwrap(), key);
+    }
+
+    #[test]
+    fn unwrap_rejects_tampering() {
+        let mut wrapped = hex::decode("1fa68b0a8112b447aef34bd8fb5a7b829d3e862371d2cfe5").unwrap();
+        // every bit flip anywhere in the ciphertext fails the check
+        for i in 0..wrapped.len() {
+            for bit in [0x01, 0x80] {
+                wrapped[i] ^= bit;
+                assert_eq!(
+                    unwrap(kek(16), &wrapped),
+                    Err(CryptoError::DecryptionFailed)
+                );
+                wrapped[i] ^= bit;
+            }
+        }
+        assert!(unwrap(kek(16), &wrapped).is_ok());
+        assert_eq!(
+            unwrap(kek(24), &wrapped),
+            Err(CryptoError::DecryptionFailed)
+        );
+
+        assert_eq!(wrap(kek(16), &[0; 8]), Err(CryptoError::InvalidLength));
+        assert_eq!(wrap(kek(16), &[0; 20]), Err(CryptoError::InvalidLength));
+        assert_eq!(
+            unwrap(kek(16), &wrapped[..16]),
+            Err(CryptoError::InvalidLength)
+        );
+        assert_eq!(
+            unwrap(kek(16), &wrapped[..23]),
+            Err(CryptoError::InvalidLength)
+        );
+    }
+}
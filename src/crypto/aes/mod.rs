@@ -1,32 +1,73 @@
+pub mod cbc;
 mod cipher;
+pub mod ctr;
 mod key;
+pub mod keywrap;
 mod state;
 mod utils;
 mod word;
 
+use super::error::CryptoError;
+use alloc::vec::Vec;
 use cipher::{cipher, inv_cipher};
 use key::Key;
+use word::Word;
 
-pub fn encrypt(key: Key, plaintext: [u8; 16]) -> Result<[u8; 16], String> {
+/// Number of rounds and the expanded key
+fn key_schedule(key: &Key) -> Result<(usize, Vec<Word>), CryptoError> {
     let nr = match key.get_size_in_bytes() {
         16 => 10,
         24 => 12,
         32 => 14,
-        _ => return Err("Key has wrong length".to_owned()),
+        _ => return Err(CryptoError::InvalidKey),
     };
-    let w = key.get_round_keys().unwrap();
-    Ok(cipher(plaintext, nr, w))
+    Ok((nr, key.get_round_keys().unwrap()))
 }
 
-pub fn decrypt(key: Key, plaintext: [u8; 16]) -> Result<[u8; 16], String> {
-    let nr = match key.get_size_in_bytes() {
-        16 => 10,
-        24 => 12,
-        32 => 14,
-        _ => return Err("Key has wrong length".to_owned()),
-    };
-    let w = key.get_round_keys().unwrap();
-    Ok(inv_cipher(plaintext, nr, w))
+/// Single block cipher that modes of operation can be written against, also usable as
+/// `&dyn BlockCipher`
+pub trait BlockCipher {
+    /// Size of a block in bytes
+    fn block_size(&self) -> usize {
+        16
+    }
+
+    fn encrypt_block(&self, block: &mut [u8; 16]);
+    fn decrypt_block(&self, block: &mut [u8; 16]);
+}
+
+/// AES with the key schedule computed once, for encrypting many blocks with the same key
+#[derive(Clone)]
+pub struct Aes {
+    nr: usize,
+    w: Vec<Word>,
+}
+
+impl Aes {
+    pub fn new(key: &Key) -> Result<Self, CryptoError> {
+        let (nr, w) = key_schedule(key)?;
+        Ok(Self { nr, w })
+    }
+}
+
+impl BlockCipher for Aes {
+    fn encrypt_block(&self, block: &mut [u8; 16]) {
+        *block = cipher(*block, self.nr, &self.w);
+    }
+
+    fn decrypt_block(&self, block: &mut [u8; 16]) {
+        *block = inv_cipher(*block, self.nr, &self.w);
+    }
+}
+
+pub fn encrypt(key: Key, plaintext: [u8; 16]) -> Result<[u8; 16], CryptoError> {
+    let (nr, w) = key_schedule(&key)?;
+    Ok(cipher(plaintext, nr, &w))
+}
+
+pub fn decrypt(key: Key, plaintext: [u8; 16]) -> Result<[u8; 16], CryptoError> {
+    let (nr, w) = key_schedule(&key)?;
+    Ok(inv_cipher(plaintext, nr, &w))
 }
 
 #[cfg(test)]
@@ -72,4 +113,26 @@ mod tests {
 
         assert_eq!(super::decrypt(key, plaintext).unwrap(), ciphertext);
     }
+
+    #[test]
+    fn block_cipher() {
+        let key = Key::from([
+            0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6, 0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf,
+            0x4f, 0x3c,
+        ]);
+        let plaintext = [
+            0x32, 0x43, 0xf6, 0xa8, 0x88, 0x5a, 0x30, 0x8d, 0x31, 0x31, 0x98, 0xa2, 0xe0, 0x37,
+            0x07, 0x34,
+        ];
+        let aes = Aes::new(&key).unwrap();
+
+        let mut block = plaintext;
+        aes.encrypt_block(&mut block);
+        assert_eq!(block, super::encrypt(key, plaintext).unwrap());
+        aes.decrypt_block(&mut block);
+        assert_eq!(block, plaintext);
+
+        assert!(Aes::new(&Key::new(vec![0; 16]).unwrap()).is_ok());
+        assert_eq!(aes.block_size(), 16);
+    }
 }
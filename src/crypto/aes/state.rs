@@ -77,22 +77,22 @@ impl State {
 
     pub fn mix_columns(&mut self) {
         for c in 0..4 {
-            let s_0c = utils::gf8_mul(0x02, self.state[0][c])
-                ^ utils::gf8_mul(0x03, self.state[1][c])
+            let s_0c = utils::MUL_02[self.state[0][c] as usize]
+                ^ utils::MUL_03[self.state[1][c] as usize]
                 ^ self.state[2][c]
                 ^ self.state[3][c];
             let s_1c = self.state[0][c]
-                ^ utils::gf8_mul(0x02, self.state[1][c])
-                ^ utils::gf8_mul(0x03, self.state[2][c])
+                ^ utils::MUL_02[self.state[1][c] as usize]
+                ^ utils::MUL_03[self.state[2][c] as usize]
                 ^ self.state[3][c];
             let s_2c = self.state[0][c]
                 ^ self.state[1][c]
-                ^ utils::gf8_mul(0x02, self.state[2][c])
-                ^ utils::gf8_mul(0x03, self.state[3][c]);
-            let s_3c = utils::gf8_mul(0x03, self.state[0][c])
+                ^ utils::MUL_02[self.state[2][c] as usize]
+                ^ utils::MUL_03[self.state[3][c] as usize];
+            let s_3c = utils::MUL_03[self.state[0][c] as usize]
                 ^ self.state[1][c]
                 ^ self.state[2][c]
-                ^ utils::gf8_mul(0x02, self.state[3][c]);
+                ^ utils::MUL_02[self.state[3][c] as usize];
 
             self.state[0][c] = s_0c;
             self.state[1][c] = s_1c;
@@ -103,22 +103,22 @@ impl State {
 
     pub fn inv_mix_columns(&mut self) {
         for c in 0..4 {
-            let s_0c = utils::gf8_mul(0x0e, self.state[0][c])
-                ^ utils::gf8_mul(0x0b, self.state[1][c])
-                ^ utils::gf8_mul(0x0d, self.state[2][c])
-                ^ utils::gf8_mul(0x09, self.state[3][c]);
-            let s_1c = utils::gf8_mul(0x09, self.state[0][c])
-                ^ utils::gf8_mul(0x0e, self.state[1][c])
-                ^ utils::gf8_mul(0x0b, self.state[2][c])
-                ^ utils::gf8_mul(0x0d, self.state[3][c]);
-            let s_2c = utils::gf8_mul(0x0d, self.state[0][c])
-                ^ utils::gf8_mul(0x09, self.state[1][c])
-                ^ utils::gf8_mul(0x0e, self.state[2][c])
-                ^ utils::gf8_mul(0x0b, self.state[3][c]);
-            let s_3c = utils::gf8_mul(0x0b, self.state[0][c])
-                ^ utils::gf8_mul(0x0d, self.state[1][c])
-                ^ utils::gf8_mul(0x09, self.state[2][c])
-                ^ utils::gf8_mul(0x0e, self.state[3][c]);
+            let s_0c = utils::MUL_0E[self.state[0][c] as usize]
+                ^ utils::MUL_0B[self.state[1][c] as usize]
+                ^ utils::MUL_0D[self.state[2][c] as usize]
+                ^ utils::MUL_09[self.state[3][c] as usize];
+            let s_1c = utils::MUL_09[self.state[0][c] as usize]
+                ^ utils::MUL_0E[self.state[1][c] as usize]
+                ^ utils::MUL_0B[self.state[2][c] as usize]
+                ^ utils::MUL_0D[self.state[3][c] as usize];
+            let s_2c = utils::MUL_0D[self.state[0][c] as usize]
+                ^ utils::MUL_09[self.state[1][c] as usize]
+                ^ utils::MUL_0E[self.state[2][c] as usize]
+                ^ utils::MUL_0B[self.state[3][c] as usize];
+            let s_3c = utils::MUL_0B[self.state[0][c] as usize]
+                ^ utils::MUL_0D[self.state[1][c] as usize]
+                ^ utils::MUL_09[self.state[2][c] as usize]
+                ^ utils::MUL_0E[self.state[3][c] as usize];
 
             self.state[0][c] = s_0c;
             self.state[1][c] = s_1c;
@@ -320,4 +320,41 @@ mod tests {
 
         assert_eq!(state.state, valid_state_after.state);
     }
+
+    #[test]
+    fn mix_columns_tables_match_gf8_mul() {
+        // the matrix multiplication of FIPS-197 - Section 5.1.3 / 5.3.3 with gf8_mul
+        let reference = |s: [[u8; 4]; 4], m: [u8; 4]| {
+            let mut out = [[0; 4]; 4];
+            for c in 0..4 {
+                for r in 0..4 {
+                    out[r][c] = (0..4).fold(0, |acc, i| {
+                        acc ^ utils::gf8_mul(m[(4 + i - r) % 4], s[i][c])
+                    });
+                }
+            }
+            out
+        };
+
+        for seed in 0..64u8 {
+            let mut input = [0; 16];
+            for (i, b) in input.iter_mut().enumerate() {
+                *b = seed.wrapping_mul(37) ^ (i as u8).wrapping_mul(101);
+            }
+
+            let mut state = State::new(input);
+            state.mix_columns();
+            assert_eq!(
+                state.state,
+                reference(State::new(input).state, [2, 3, 1, 1])
+            );
+
+            let mut state = State::new(input);
+            state.inv_mix_columns();
+            assert_eq!(
+                state.state,
+                reference(State::new(input).state, [0x0e, 0x0b, 0x0d, 0x09])
+            );
+        }
+    }
 }
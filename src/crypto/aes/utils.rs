@@ -61,7 +61,7 @@ pub fn inv_sbox(byte: u8) -> u8 {
     field[byte as usize]
 }
 
-pub fn xtimes(byte: u8) -> u8 {
+pub const fn xtimes(byte: u8) -> u8 {
     if byte & 0x80 == 0 {
         byte << 1
     } else {
@@ -69,7 +69,7 @@ pub fn xtimes(byte: u8) -> u8 {
     }
 }
 
-pub fn gf8_mul(mut a: u8, mut b: u8) -> u8 {
+pub const fn gf8_mul(mut a: u8, mut b: u8) -> u8 {
     let mut res = 0;
     while b != 0 {
         if b & 1 != 0 {
@@ -81,6 +81,26 @@ pub fn gf8_mul(mut a: u8, mut b: u8) -> u8 {
     res
 }
 
+/// `gf8_mul(m, x)` for every `x`, evaluated at compile time
+const fn gf8_mul_table(m: u8) -> [u8; 256] {
+    let mut table = [0; 256];
+    let mut x = 0;
+    while x < 256 {
+        table[x] = gf8_mul(m, x as u8);
+        x += 1;
+    }
+    table
+}
+
+/// Multiplication tables for the fixed coefficients of MixColumns and InvMixColumns
+/// (FIPS-197 - Section 5.1.3 and 5.3.3)
+pub const MUL_02: [u8; 256] = gf8_mul_table(0x02);
+pub const MUL_03: [u8; 256] = gf8_mul_table(0x03);
+pub const MUL_09: [u8; 256] = gf8_mul_table(0x09);
+pub const MUL_0B: [u8; 256] = gf8_mul_table(0x0b);
+pub const MUL_0D: [u8; 256] = gf8_mul_table(0x0d);
+pub const MUL_0E: [u8; 256] = gf8_mul_table(0x0e);
+
 #[cfg(test)]
 #[allow(non_snake_case)]
 mod tests {
@@ -108,4 +128,20 @@ mod tests {
         assert_eq!(gf8_mul(0x57, 0x40), 0x1c);
         assert_eq!(gf8_mul(0x57, 0x80), 0x38);
     }
+
+    #[test]
+    fn gf8_mul_tables() {
+        for (table, m) in [
+            (MUL_02, 0x02),
+            (MUL_03, 0x03),
+            (MUL_09, 0x09),
+            (MUL_0B, 0x0b),
+            (MUL_0D, 0x0d),
+            (MUL_0E, 0x0e),
+        ] {
+            for x in 0..=255 {
+                assert_eq!(table[x as usize], gf8_mul(m, x));
+            }
+        }
+    }
 }
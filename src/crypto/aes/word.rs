@@ -1,4 +1,4 @@
-use std::ops::{Index, IndexMut};
+use core::ops::{Index, IndexMut};
 
 use super::utils::{inv_sbox, sbox};
 
@@ -40,7 +40,7 @@ impl Word {
     }
 }
 
-impl std::ops::BitXor for Word {
+impl core::ops::BitXor for Word {
     type Output = Self;
 
     fn bitxor(self, rhs: Self) -> Self::Output {
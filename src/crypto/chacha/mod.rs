@@ -1,9 +1,16 @@
-mod state;
+pub mod state;
 mod utils;
 
-use std::vec;
+use alloc::{vec, vec::Vec};
 
 use self::state::chacha20_block;
+use super::{error::CryptoError, nonce::ChaChaNonce};
+
+/// Block counter `counter + j`, `None` if the 32 bit counter would wrap around and reuse key
+/// stream
+fn block_counter(counter: u32, j: usize) -> Option<u32> {
+    u32::try_from(j).ok()?.checked_add(counter)
+}
 
 /// RFC 7539 - Section 2.4.1 - The ChaCha20 Encryption Algorithm
 ///
@@ -24,16 +31,20 @@ use self::state::chacha20_block;
 ///     return encrypted_message
 /// end
 /// ```
+///
+/// Fails if the plaintext is too long for the 32 bit block counter starting at `counter`
 pub fn chacha20_encrypt(
     key: [u8; 32],
-    nonce: [u8; 12],
+    nonce: ChaChaNonce,
     counter: u32,
     plaintext: Vec<u8>,
-) -> Vec<u8> {
+) -> Result<Vec<u8>, CryptoError> {
+    let nonce = *nonce;
     let mut ciphertext: Vec<u8> = vec![];
 
     for j in 0..plaintext.len() / 64 {
-        let key_stream = chacha20_block(key, nonce, counter + j as u32);
+        let block_counter = block_counter(counter, j).ok_or(CryptoError::CounterExhausted)?;
+        let key_stream = chacha20_block(key, nonce, block_counter);
         let block = &plaintext[(j * 64)..(j * 64 + 64)];
 
         assert_eq!(key_stream.len(), 64);
@@ -44,9 +55,10 @@ pub fn chacha20_encrypt(
         });
     }
 
-    if (plaintext.len() % 64) != 0 {
+    if !plaintext.len().is_multiple_of(64) {
         let j = plaintext.len() / 64;
-        let key_stream = chacha20_block(key, nonce, counter + j as u32);
+        let block_counter = block_counter(counter, j).ok_or(CryptoError::CounterExhausted)?;
+        let key_stream = chacha20_block(key, nonce, block_counter);
         let block = &plaintext[(j * 64)..plaintext.len()];
 
         (0..plaintext.len() % 64).for_each(|i| {
@@ -54,19 +66,147 @@ pub fn chacha20_encrypt(
         });
     }
 
-    ciphertext
+    Ok(ciphertext)
 }
 
 /// RFC 7539 - Section 2.4.1 - The ChaCha20 Encryption Algorithm (reversed)
 pub fn chacha20_decrypt(
     key: [u8; 32],
-    nonce: [u8; 12],
+    nonce: ChaChaNonce,
     counter: u32,
     ciphertext: Vec<u8>,
-) -> Vec<u8> {
+) -> Result<Vec<u8>, CryptoError> {
     chacha20_encrypt(key, nonce, counter, ciphertext)
 }
 
+/// Key stream of a `(key, nonce, counter)` triple that can be applied at any byte position,
+/// caching the most recently computed block
+#[cfg(feature = "std")]
+struct KeyStream {
+    key: [u8; 32],
+    nonce: [u8; 12],
+    counter: u32,
+    block: (usize, [u8; 64]),
+}
+
+#[cfg(feature = "std")]
+impl KeyStream {
+    fn new(key: [u8; 32], nonce: ChaChaNonce, counter: u32) -> Self {
+        let nonce = *nonce;
+        Self {
+            key,
+            nonce,
+            counter,
+            block: (0, chacha20_block(key, nonce, counter)),
+        }
+    }
+
+    /// XOR `buf` with the key stream starting at byte `position`, fails once the block counter
+    /// would wrap around
+    fn apply(&mut self, position: usize, buf: &mut [u8]) -> std::io::Result<()> {
+        for (i, b) in buf.iter_mut().enumerate() {
+            let (j, offset) = ((position + i) / 64, (position + i) % 64);
+            if self.block.0 != j {
+                let counter = block_counter(self.counter, j).ok_or_else(|| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::InvalidInput,
+                        CryptoError::CounterExhausted,
+                    )
+                })?;
+                self.block = (j, chacha20_block(self.key, self.nonce, counter));
+            }
+            *b ^= self.block.1[offset];
+        }
+        Ok(())
+    }
+}
+
+/// Streaming ChaCha20, every byte written is XORed with the key stream and passed on to the
+/// inner writer. The key stream position only advances by the number of bytes the inner writer
+/// accepted, so writes don't have to be aligned to the 64 byte blocks.
+#[cfg(feature = "std")]
+pub struct ChaChaWriter<W: std::io::Write> {
+    inner: W,
+    key_stream: KeyStream,
+    /// Number of bytes written to the inner writer so far
+    position: usize,
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> ChaChaWriter<W> {
+    pub fn new(inner: W, key: [u8; 32], nonce: ChaChaNonce, counter: u32) -> Self {
+        Self {
+            inner,
+            key_stream: KeyStream::new(key, nonce, counter),
+            position: 0,
+        }
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> std::io::Write for ChaChaWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let mut out = buf.to_vec();
+        self.key_stream.apply(self.position, &mut out)?;
+
+        let n = self.inner.write(&out)?;
+        self.position += n;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Streaming ChaCha20 decryption, ciphertext is read from the inner reader and XORed with the
+/// key stream on the way out. Short reads of the inner reader only advance the key stream by the
+/// number of bytes actually read.
+#[cfg(feature = "std")]
+pub struct ChaChaReader<R: std::io::Read> {
+    inner: R,
+    key_stream: KeyStream,
+    /// Number of bytes read from the inner reader so far
+    position: usize,
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::Read> ChaChaReader<R> {
+    pub fn new(inner: R, key: [u8; 32], nonce: ChaChaNonce, counter: u32) -> Self {
+        Self {
+            inner,
+            key_stream: KeyStream::new(key, nonce, counter),
+            position: 0,
+        }
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::Read> std::io::Read for ChaChaReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.key_stream.apply(self.position, &mut buf[..n])?;
+        self.position += n;
+        Ok(n)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -78,14 +218,14 @@ mod test {
             0x0e, 0x0f, 0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b,
             0x1c, 0x1d, 0x1e, 0x1f,
         ];
-        let nonce: [u8; 12] = [
+        let nonce = ChaChaNonce::from_bytes([
             0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x4a, 0x00, 0x00, 0x00, 0x00,
-        ];
+        ]);
         let counter: u32 = 1;
 
         let plaintext = "Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.".as_bytes().to_vec();
 
-        let ciphertext = chacha20_encrypt(key, nonce, counter, plaintext);
+        let ciphertext = chacha20_encrypt(key, nonce, counter, plaintext).unwrap();
 
         let valid_ciphertetxt = vec![
             0x6e, 0x2e, 0x35, 0x9a, 0x25, 0x68, 0xf9, 0x80, 0x41, 0xba, 0x07, 0x28, 0xdd, 0x0d,
@@ -120,15 +260,157 @@ mod test {
             0x0e, 0x0f, 0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b,
             0x1c, 0x1d, 0x1e, 0x1f,
         ];
-        let nonce: [u8; 12] = [
+        let nonce = ChaChaNonce::from_bytes([
             0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x4a, 0x00, 0x00, 0x00, 0x00,
-        ];
+        ]);
         let counter: u32 = 1;
 
-        let plaintext = chacha20_decrypt(key, nonce, counter, ciphertext);
+        let plaintext = chacha20_decrypt(key, nonce, counter, ciphertext).unwrap();
 
         let valid_plaintext = "Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.".as_bytes().to_vec();
 
         assert_eq!(plaintext, valid_plaintext);
     }
+
+    #[test]
+    fn block_from_public_state() {
+        let key = [0x42; 32];
+        let nonce = [7; 12];
+
+        let mut state = state::State::new(key, nonce, 5);
+        let mut working_state = state;
+        for _ in 0..10 {
+            for (a, b, c, d) in [
+                (0, 4, 8, 12),
+                (1, 5, 9, 13),
+                (2, 6, 10, 14),
+                (3, 7, 11, 15),
+                (0, 5, 10, 15),
+                (1, 6, 11, 12),
+                (2, 7, 8, 13),
+                (3, 4, 9, 14),
+            ] {
+                working_state.quarter_round(a, b, c, d);
+            }
+        }
+        state += working_state;
+
+        assert_eq!(state.serialize(), chacha20_block(key, nonce, 5));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn writer_round_trip() {
+        use std::io::Write;
+
+        let key = [0x42; 32];
+        let nonce = ChaChaNonce::from_bytes([7; 12]);
+        let data: Vec<u8> = (0..100_000u32).map(|i| (i * 13) as u8).collect();
+        let expected = chacha20_encrypt(key, nonce, 1, data.clone()).unwrap();
+
+        for chunk in [1, 7, 63, 64, 65, 1000, data.len()] {
+            let mut writer = ChaChaWriter::new(Vec::new(), key, nonce, 1);
+            for c in data.chunks(chunk) {
+                writer.write_all(c).unwrap();
+            }
+            writer.flush().unwrap();
+            let ciphertext = writer.into_inner();
+            assert_eq!(ciphertext, expected);
+
+            let mut writer = ChaChaWriter::new(Vec::new(), key, nonce, 1);
+            std::io::copy(&mut ciphertext.as_slice(), &mut writer).unwrap();
+            assert_eq!(writer.into_inner(), data);
+        }
+    }
+
+    /// Returns at most `self.1` bytes per call
+    #[cfg(feature = "std")]
+    struct ShortReader<'a>(&'a [u8], usize);
+
+    #[cfg(feature = "std")]
+    impl std::io::Read for ShortReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let n = buf.len().min(self.1).min(self.0.len());
+            buf[..n].copy_from_slice(&self.0[..n]);
+            self.0 = &self.0[n..];
+            Ok(n)
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn reader_round_trip() {
+        use std::io::Read;
+
+        let key = [0x42; 32];
+        let nonce = ChaChaNonce::from_bytes([7; 12]);
+        let data: Vec<u8> = (0..100_000u32).map(|i| (i * 13) as u8).collect();
+        let ciphertext = chacha20_encrypt(key, nonce, 1, data.clone()).unwrap();
+
+        for limit in [1, 7, 63, 64, 65, 1000, data.len()] {
+            let mut reader = ChaChaReader::new(ShortReader(&ciphertext, limit), key, nonce, 1);
+            let mut plaintext = Vec::new();
+            reader.read_to_end(&mut plaintext).unwrap();
+            assert_eq!(plaintext, data);
+        }
+
+        // reading with a small buffer that doesn't divide the block size
+        let mut reader = ChaChaReader::new(ciphertext.as_slice(), key, nonce, 1);
+        let mut plaintext = Vec::new();
+        let mut buf = [0; 50];
+        loop {
+            let n = reader.read(&mut buf).unwrap();
+            if n == 0 {
+                break;
+            }
+            plaintext.extend_from_slice(&buf[..n]);
+        }
+        assert_eq!(plaintext, data);
+    }
+
+    #[test]
+    fn counter_exhausted() {
+        let key = [0x42; 32];
+        let nonce = ChaChaNonce::from_bytes([7; 12]);
+
+        // the last block counter is u32::MAX
+        assert_eq!(
+            chacha20_encrypt(key, nonce, u32::MAX, vec![0; 64])
+                .unwrap()
+                .len(),
+            64
+        );
+        assert_eq!(
+            chacha20_encrypt(key, nonce, u32::MAX, vec![0; 65]),
+            Err(CryptoError::CounterExhausted)
+        );
+        assert_eq!(
+            chacha20_decrypt(key, nonce, u32::MAX - 1, vec![0; 129]),
+            Err(CryptoError::CounterExhausted)
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn stream_counter_exhausted() {
+        use std::io::{Read, Write};
+
+        let key = [0x42; 32];
+        let nonce = ChaChaNonce::from_bytes([7; 12]);
+        let expected = chacha20_encrypt(key, nonce, u32::MAX, vec![0; 64]).unwrap();
+
+        let mut writer = ChaChaWriter::new(Vec::new(), key, nonce, u32::MAX);
+        writer.write_all(&[0; 60]).unwrap();
+        let err = writer.write(&[0; 5]).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        writer.write_all(&[0; 4]).unwrap();
+        assert!(writer.write(&[0]).is_err());
+        assert_eq!(writer.into_inner(), expected);
+
+        let mut reader = ChaChaReader::new([0; 65].as_slice(), key, nonce, u32::MAX);
+        let mut buf = [0; 64];
+        reader.read_exact(&mut buf).unwrap();
+        assert_eq!(buf.to_vec(), expected);
+        assert!(reader.read(&mut buf).is_err());
+    }
 }
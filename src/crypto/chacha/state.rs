@@ -1,11 +1,16 @@
 use super::utils;
 
+/// RFC 7539 - Section 2.3 - The 4x4 matrix of 32 bit words the block function works on
+///
+/// Public for building custom constructions on top of the ChaCha core. The words can only be
+/// set through `new` and modified with `quarter_round` and `+=`.
 #[derive(PartialEq, Debug, Clone, Copy)]
-struct State {
+pub struct State {
     data: [u32; 16],
 }
 
 impl State {
+    /// RFC 7539 - Section 2.3 - constants | key | block counter | nonce, all words little endian
     pub fn new(key: [u8; 32], nonce: [u8; 12], counter: u32) -> Self {
         let mut data = [0u32; 16];
         (data[0], data[1], data[2], data[3]) = (0x61707865, 0x3320646e, 0x79622d32, 0x6b206574);
@@ -46,6 +51,7 @@ impl State {
         self.quarter_round(3, 4, 9, 14);
     }
 
+    /// Words in little endian byte order
     pub fn serialize(self) -> [u8; 64] {
         let mut result = [0u8; 64];
 
@@ -60,7 +66,7 @@ impl State {
     }
 }
 
-impl std::ops::AddAssign for State {
+impl core::ops::AddAssign for State {
     fn add_assign(&mut self, rhs: Self) {
         self.data.iter_mut().enumerate().for_each(|(i, e)| {
             *e = e.wrapping_add(rhs.data[i]);
@@ -98,7 +104,6 @@ pub fn chacha20_block(key: [u8; 32], nonce: [u8; 12], counter: u32) -> [u8; 64]
 }
 
 #[cfg(test)]
-
 mod test {
     use super::*;
 
@@ -0,0 +1,105 @@
+#[cfg(any(feature = "getrandom", all(feature = "std", unix)))]
+use super::rng::Rng;
+use crate::math::bignum_fast::BignumFast;
+#[cfg(any(feature = "getrandom", all(feature = "std", unix)))]
+use alloc::vec;
+
+/// Generator of the RFC 3526 MODP groups
+pub const MODP_GENERATOR: u32 = 2;
+
+/// RFC 3526 - Section 2 - 1536-bit MODP group prime with generator `MODP_GENERATOR`
+pub const MODP_1536_P: &str = "\
+     FFFFFFFFFFFFFFFFC90FDAA22168C234C4C6628B80DC1CD129024E088A67CC74\
+     020BBEA63B139B22514A08798E3404DDEF9519B3CD3A431B302B0A6DF25F1437\
+     4FE1356D6D51C245E485B576625E7EC6F44C42E9A637ED6B0BFF5CB6F406B7ED\
+     EE386BFB5A899FA5AE9F24117C4B1FE649286651ECE45B3DC2007CB8A163BF05\
+     98DA48361C55D39A69163FA8FD24CF5F83655D23DCA3AD961C62F356208552BB\
+     9ED529077096966D670C354E4ABC9804F1746C08CA237327FFFFFFFFFFFFFFFF";
+
+/// RFC 3526 - Section 3 - 2048-bit MODP group prime with generator `MODP_GENERATOR`
+pub const MODP_2048_P: &str = "\
+     FFFFFFFFFFFFFFFFC90FDAA22168C234C4C6628B80DC1CD129024E088A67CC74\
+     020BBEA63B139B22514A08798E3404DDEF9519B3CD3A431B302B0A6DF25F1437\
+     4FE1356D6D51C245E485B576625E7EC6F44C42E9A637ED6B0BFF5CB6F406B7ED\
+     EE386BFB5A899FA5AE9F24117C4B1FE649286651ECE45B3DC2007CB8A163BF05\
+     98DA48361C55D39A69163FA8FD24CF5F83655D23DCA3AD961C62F356208552BB\
+     9ED529077096966D670C354E4ABC9804F1746C08CA18217C32905E462E36CE3B\
+     E39E772C180E86039B2783A2EC07A28FB5C55DF06F4C52C9DE2BCBF695581718\
+     3995497CEA956AE515D2261898FA051015728E5A8AACAA68FFFFFFFFFFFFFFFF";
+
+/// Generate a Diffie-Hellman key pair `(x, g^x mod p)` with a private exponent `x` in `[2, p - 2]`
+///
+/// `N` has to be at least twice the size of `p` in bytes, so that products mod p fit
+#[cfg(any(feature = "getrandom", all(feature = "std", unix)))]
+pub fn generate_keypair<const N: usize>(
+    p: &BignumFast<N>,
+    g: &BignumFast<N>,
+    rng: &mut impl Rng,
+) -> (BignumFast<N>, BignumFast<N>) {
+    let three = BignumFast::from(3u32);
+    assert!(*p > three, "p has to be larger than 3");
+
+    // 8 additional bytes make the bias of the reduction negligible
+    let mut buf = vec![0; (p.len() + 8).min(N)];
+    rng.fill_bytes(&mut buf);
+    let x = BignumFast::from_big_endian(&buf).expect("buffer fits into N bytes");
+    let x = (x % p.sub_ref(&three)).add_ref(&BignumFast::from(2u32));
+
+    let public = g.clone().pow_mod(x.clone(), p);
+    (x, public)
+}
+
+/// Shared secret `their_public^my_private mod p`
+pub fn shared_secret<const N: usize>(
+    their_public: &BignumFast<N>,
+    my_private: &BignumFast<N>,
+    p: &BignumFast<N>,
+) -> BignumFast<N> {
+    their_public.clone().pow_mod(my_private.clone(), p)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::rng::OsRng;
+
+    #[test]
+    fn exchange_small_safe_prime() {
+        // 2039 = 2 * 1019 + 1, 4 = 2^2 generates the subgroup of prime order 1019
+        let p: BignumFast<8> = BignumFast::from(2039u32);
+        let g = BignumFast::from(4u32);
+
+        for _ in 0..20 {
+            let (a, a_pub) = generate_keypair(&p, &g, &mut OsRng);
+            let (b, b_pub) = generate_keypair(&p, &g, &mut OsRng);
+
+            assert!(a >= BignumFast::from(2u32) && a <= BignumFast::from(2037u32));
+            assert_eq!(shared_secret(&b_pub, &a, &p), shared_secret(&a_pub, &b, &p));
+        }
+    }
+
+    #[test]
+    fn exchange_64_bit() {
+        // largest 64 bit safe prime 2^64 - 1469
+        let p: BignumFast<16> = BignumFast::from(0xffff_ffff_ffff_fa43u64);
+        let g = BignumFast::from(MODP_GENERATOR);
+
+        let (a, a_pub) = generate_keypair(&p, &g, &mut OsRng);
+        let (b, b_pub) = generate_keypair(&p, &g, &mut OsRng);
+
+        let s = shared_secret(&b_pub, &a, &p);
+        assert_eq!(s, shared_secret(&a_pub, &b, &p));
+        assert!(s < p);
+    }
+
+    #[test]
+    fn modp_parameters() {
+        let p: BignumFast<384> = BignumFast::try_from_hex_string(MODP_1536_P).unwrap();
+        assert_eq!(p.bit_length(), 1536);
+        assert_eq!(p.rem_small(1 << 32), 0xffff_ffff);
+
+        let p: BignumFast<512> = BignumFast::try_from_hex_string(MODP_2048_P).unwrap();
+        assert_eq!(p.bit_length(), 2048);
+        assert_eq!(p.rem_small(1 << 32), 0xffff_ffff);
+    }
+}
@@ -0,0 +1,60 @@
+use alloc::vec::Vec;
+
+/// Hash function usable by `Hmac` and `hkdf`
+pub trait Digest: Clone {
+    /// Size of the input blocks in bytes, used to pad the HMAC key
+    const BLOCK_SIZE: usize;
+    /// Size of the hash value in bytes
+    const OUTPUT_SIZE: usize;
+
+    fn new() -> Self;
+    fn update(&mut self, data: &[u8]);
+    fn finalize(self) -> Vec<u8>;
+
+    /// Hash `data` in one call
+    fn digest(data: &[u8]) -> Vec<u8> {
+        let mut h = Self::new();
+        h.update(data);
+        h.finalize()
+    }
+}
+
+/// Hash everything `r` yields, reading it in chunks so that large files don't have to be loaded
+/// into memory
+#[cfg(feature = "std")]
+pub fn hash_reader<D: Digest, R: std::io::Read>(r: &mut R) -> std::io::Result<Vec<u8>> {
+    let mut h = D::new();
+    let mut buf = [0; 8192];
+
+    loop {
+        match r.read(&mut buf) {
+            Ok(0) => return Ok(h.finalize()),
+            Ok(n) => h.update(&buf[..n]),
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::crypto::sha3::{sha3_256, sha3_512, Sha3_256, Sha3_512};
+
+    #[test]
+    fn hash_reader_matches_one_shot() {
+        let data: Vec<u8> = (0..3 * 1024 * 1024u32).map(|i| (i * 31) as u8).collect();
+
+        let mut cursor = std::io::Cursor::new(&data);
+        assert_eq!(
+            hash_reader::<Sha3_256, _>(&mut cursor).unwrap(),
+            sha3_256(&data)
+        );
+
+        let mut empty: &[u8] = &[];
+        assert_eq!(
+            hash_reader::<Sha3_512, _>(&mut empty).unwrap(),
+            sha3_512(b"")
+        );
+    }
+}
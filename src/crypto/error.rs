@@ -0,0 +1,52 @@
+/// Error of the higher level operations, so that callers can propagate failures of different
+/// primitives with `?`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CryptoError {
+    /// The key has the wrong size or does not fit the chosen parameters
+    InvalidKey,
+    /// The input has a length the operation can't handle
+    InvalidLength,
+    /// The message does not fit into a single operation with this key
+    MessageTooLong,
+    /// Padding or integrity check failed. Deliberately carries no details, to not act as an
+    /// oracle
+    DecryptionFailed,
+    /// A nonce sequence or key stream counter has no values left
+    CounterExhausted,
+}
+
+impl core::fmt::Display for CryptoError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            CryptoError::InvalidKey => write!(f, "invalid key"),
+            CryptoError::InvalidLength => write!(f, "invalid input length"),
+            CryptoError::MessageTooLong => write!(f, "message too long"),
+            CryptoError::DecryptionFailed => write!(f, "decryption error"),
+            CryptoError::CounterExhausted => write!(f, "counter exhausted"),
+        }
+    }
+}
+
+impl core::error::Error for CryptoError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, string::ToString};
+
+    #[test]
+    fn display_and_propagation() {
+        for (e, msg) in [
+            (CryptoError::InvalidKey, "invalid key"),
+            (CryptoError::InvalidLength, "invalid input length"),
+            (CryptoError::MessageTooLong, "message too long"),
+            (CryptoError::DecryptionFailed, "decryption error"),
+            (CryptoError::CounterExhausted, "counter exhausted"),
+        ] {
+            assert_eq!(e.to_string(), msg);
+
+            let f = || -> Result<(), Box<dyn core::error::Error>> { Err(e)? };
+            assert_eq!(f().unwrap_err().to_string(), msg);
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use super::digest::Digest;
+use super::error::CryptoError;
+use super::hmac::Hmac;
+use alloc::{vec, vec::Vec};
+
+/// HKDF-Extract (RFC 5869 - Section 2.2)
+///
+/// `PRK = HMAC-Hash(salt, IKM)`, an empty salt acts as `HashLen` zero bytes
+pub fn extract<D: Digest>(salt: &[u8], ikm: &[u8]) -> Vec<u8> {
+    Hmac::<D>::mac(salt, ikm)
+}
+
+/// HKDF-Expand (RFC 5869 - Section 2.3)
+///
+/// `T(i) = HMAC-Hash(PRK, T(i - 1) || info || i)`, the output is the first `length` bytes of
+/// `T(1) || T(2) || ...`. At most `255 * HashLen` bytes can be derived.
+pub fn expand<D: Digest>(prk: &[u8], info: &[u8], length: usize) -> Result<Vec<u8>, CryptoError> {
+    if length > 255 * D::OUTPUT_SIZE {
+        return Err(CryptoError::InvalidLength);
+    }
+
+    let mut okm = Vec::with_capacity(length);
+    let mut t = vec![];
+    let mut i = 1u8;
+    while okm.len() < length {
+        let mut h = Hmac::<D>::new(prk);
+        h.update(&t);
+        h.update(info);
+        h.update(&[i]);
+        t = h.finalize();
+
+        okm.extend(&t);
+        i = i.wrapping_add(1);
+    }
+
+    okm.truncate(length);
+    Ok(okm)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::sha3::Sha3_256;
+    use crate::util::hex;
+
+    #[test]
+    fn hkdf_sha3_256() {
+        // inputs of the RFC 5869 test cases 1-3, the RFC only covers SHA-2 so the expected
+        // values are computed with python's hmac module
+        let cases = [
+            (
+                hex::decode("000102030405060708090a0b0c").unwrap(),
+                vec![0x0b; 22],
+                hex::decode("f0f1f2f3f4f5f6f7f8f9").unwrap(),
+                42,
+                "7d4194836f7a113a44677abc825640ade07af1c1d69a9a4b109b280a8fe54ef0",
+                "0c5160501d65021deaf2c14f5abce04c5bd2635abceeba61c2edb6e8ed726749\
+                 00557728f2c9f2c4c179",
+            ),
+            (
+                (0x60..=0xaf).collect(),
+                (0x00..=0x4f).collect(),
+                (0xb0..=0xff).collect(),
+                82,
+                "addf31835b49366ac27734104d9f1865c1c2e7c8a2ebc1fed712808e4eab677c",
+                "3dc251e66c75da6560405ec5ac10e17d851eedfbfdc13feafbec16964c25d021\
+                 bd971465a3e9c615f27769019e3f0407d84986fb0ba24e729c99834624baa21c\
+                 b623dc0098f430d52e18bbdf694df4edd8b2",
+            ),
+            (
+                vec![],
+                vec![0x0b; 22],
+                vec![],
+                42,
+                "b899e6e4b88a35f9f5d618f48b424c313f9704012763eb6295414d673365928a",
+                "bc1342cdd75c05e8b0c3ae609ce4410684d197232875073499b30cdfe2de2853\
+                 c1c1bed63d725e885e78",
+            ),
+        ];
+
+        for (salt, ikm, info, length, prk, okm) in cases {
+            let p = extract::<Sha3_256>(&salt, &ikm);
+            assert_eq!(p, hex::decode(prk).unwrap());
+            assert_eq!(
+                expand::<Sha3_256>(&p, &info, length).unwrap(),
+                hex::decode(okm).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn expand_length() {
+        let prk = extract::<Sha3_256>(b"salt", b"ikm");
+
+        assert!(expand::<Sha3_256>(&prk, b"", 0).unwrap().is_empty());
+        assert_eq!(
+            expand::<Sha3_256>(&prk, b"", 255 * 32).unwrap().len(),
+            255 * 32
+        );
+        assert_eq!(
+            expand::<Sha3_256>(&prk, b"", 255 * 32 + 1),
+            Err(CryptoError::InvalidLength)
+        );
+    }
+}
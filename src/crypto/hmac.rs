@@ -0,0 +1,160 @@
+use super::digest::Digest;
+use crate::util::{ct, zeroize};
+use alloc::vec::Vec;
+
+/// HMAC (RFC 2104) over any `Digest`
+///
+/// `H(K ^ opad || H(K ^ ipad || text))` where `K` is the key padded with zeros to the
+/// block size, keys longer than the block size are hashed first
+#[derive(Clone)]
+pub struct Hmac<D: Digest> {
+    inner: D,
+    outer: D,
+}
+
+impl<D: Digest> Hmac<D> {
+    pub fn new(key: &[u8]) -> Self {
+        let mut k = if key.len() > D::BLOCK_SIZE {
+            D::digest(key)
+        } else {
+            key.to_vec()
+        };
+        k.resize(D::BLOCK_SIZE, 0);
+
+        // K ^ ipad, then K ^ opad in place so only one copy of the key has to be wiped
+        k.iter_mut().for_each(|b| *b ^= 0x36);
+        let mut inner = D::new();
+        inner.update(&k);
+
+        k.iter_mut().for_each(|b| *b ^= 0x36 ^ 0x5c);
+        let mut outer = D::new();
+        outer.update(&k);
+
+        zeroize(&mut k);
+
+        Self { inner, outer }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    pub fn finalize(self) -> Vec<u8> {
+        let mut outer = self.outer;
+        outer.update(&self.inner.finalize());
+        outer.finalize()
+    }
+
+    /// Check `tag` against the HMAC of the data in constant time
+    pub fn verify(self, tag: &[u8]) -> bool {
+        ct::bytes_eq(&self.finalize(), tag)
+    }
+
+    /// HMAC of `data` under `key` in one call
+    pub fn mac(key: &[u8], data: &[u8]) -> Vec<u8> {
+        let mut h = Self::new(key);
+        h.update(data);
+        h.finalize()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::sha3::{Sha3_256, Sha3_512};
+    use crate::util::hex;
+
+    /// Key and data of the RFC 4231 test cases 1-4, 6 and 7
+    fn rfc4231_inputs() -> Vec<(Vec<u8>, Vec<u8>)> {
+        vec![
+            (vec![0x0b; 20], b"Hi There".to_vec()),
+            (b"Jefe".to_vec(), b"what do ya want for nothing?".to_vec()),
+            (vec![0xaa; 20], vec![0xdd; 50]),
+            ((1..=25).collect(), vec![0xcd; 50]),
+            (
+                vec![0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First".to_vec(),
+            ),
+            (
+                vec![0xaa; 131],
+                b"This is a test using a larger than block-size key and a larger than \
+                  block-size data. The key needs to be hashed before being used by the \
+                  HMAC algorithm."
+                    .to_vec(),
+            ),
+        ]
+    }
+
+    #[test]
+    fn hmac_sha3_256() {
+        // RFC 4231 only covers SHA-2, the expected values are computed with python's hmac module
+        let expected = [
+            "ba85192310dffa96e2a3a40e69774351140bb7185e1202cdcc917589f95e16bb",
+            "c7d4072e788877ae3596bbb0da73b887c9171f93095b294ae857fbe2645e1ba5",
+            "84ec79124a27107865cedd8bd82da9965e5ed8c37b0ac98005a7f39ed58a4207",
+            "57366a45e2305321a4bc5aa5fe2ef8a921f6af8273d7fe7be6cfedb3f0aea6d7",
+            "ed73a374b96c005235f948032f09674a58c0ce555cfc1f223b02356560312c3b",
+            "65c5b06d4c3de32a7aef8763261e49adb6e2293ec8e7c61e8de61701fc63e123",
+        ];
+
+        for ((key, data), expected) in rfc4231_inputs().iter().zip(expected) {
+            assert_eq!(
+                Hmac::<Sha3_256>::mac(key, data),
+                hex::decode(expected).unwrap()
+            );
+        }
+
+        // RFC 4231 test case 5, truncated to 128 bits
+        let mac = Hmac::<Sha3_256>::mac(&[0x0c; 20], b"Test With Truncation");
+        assert_eq!(
+            mac[..16],
+            hex::decode("6e02c64537fb118057abb7fb66a23b3c").unwrap()
+        );
+    }
+
+    #[test]
+    fn hmac_sha3_512() {
+        let inputs = rfc4231_inputs();
+        let expected = [
+            "eb3fbd4b2eaab8f5c504bd3a41465aacec15770a7cabac531e482f860b5ec7ba\
+             47ccb2c6f2afce8f88d22b6dc61380f23a668fd3888bb80537c0a0b86407689e",
+            "5a4bfeab6166427c7a3647b747292b8384537cdb89afb3bf5665e4c5e709350b\
+             287baec921fd7ca0ee7a0c31d022a95e1fc92ba9d77df883960275beb4e62024",
+            "00f751a9e50695b090ed6911a4b65524951cdc15a73a5d58bb55215ea2cd839a\
+             c79d2b44a39bafab27e83fde9e11f6340b11d991b1b91bf2eee7fc872426c3a4",
+        ];
+
+        for (i, expected) in [0, 1, 4].into_iter().zip(expected) {
+            let (key, data) = &inputs[i];
+            assert_eq!(
+                Hmac::<Sha3_512>::mac(key, data),
+                hex::decode(expected).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn incremental() {
+        let (key, data) = &rfc4231_inputs()[5];
+
+        let mut h = Hmac::<Sha3_256>::new(key);
+        for chunk in data.chunks(7) {
+            h.update(chunk);
+        }
+        assert_eq!(h.finalize(), Hmac::<Sha3_256>::mac(key, data));
+    }
+
+    #[test]
+    fn verify() {
+        let (key, data) = &rfc4231_inputs()[1];
+        let mut tag = Hmac::<Sha3_256>::mac(key, data);
+
+        let mut h = Hmac::<Sha3_256>::new(key);
+        h.update(data);
+        assert!(h.clone().verify(&tag));
+        assert!(!h.clone().verify(&tag[..31]));
+
+        tag[31] ^= 1;
+        assert!(!h.verify(&tag));
+    }
+}
@@ -1,2 +1,13 @@
 pub mod aes;
 pub mod chacha;
+pub mod dh;
+pub mod digest;
+pub mod error;
+pub mod hkdf;
+pub mod hmac;
+pub mod nonce;
+// without a random source everything that draws random numbers is left out as well
+#[cfg(any(feature = "getrandom", all(feature = "std", unix)))]
+pub mod rng;
+pub mod rsa;
+pub mod sha3;
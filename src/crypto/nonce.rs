@@ -0,0 +1,137 @@
+use super::error::CryptoError;
+#[cfg(any(feature = "getrandom", all(feature = "std", unix)))]
+use super::rng;
+use core::ops::Deref;
+
+macro_rules! impl_nonce {
+    ($name:ident, $len:expr, $doc:literal) => {
+        #[doc = $doc]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub struct $name([u8; $len]);
+
+        impl $name {
+            pub fn from_bytes(bytes: [u8; $len]) -> Self {
+                Self(bytes)
+            }
+
+            /// Generate a random value from `OsRng`
+            #[cfg(any(feature = "getrandom", all(feature = "std", unix)))]
+            pub fn random() -> Self {
+                let mut buf = [0; $len];
+                rng::fill_bytes(&mut buf);
+                Self(buf)
+            }
+        }
+
+        impl Deref for $name {
+            type Target = [u8; $len];
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+    };
+}
+impl_nonce!(Iv, 16, "Initialization vector for AES-CBC");
+impl_nonce!(CtrNonce, 16, "Initial counter block for AES-CTR");
+impl_nonce!(ChaChaNonce, 12, "96 bit nonce for ChaCha20 (RFC 7539)");
+
+/// RFC 5116 - Section 3.2 - 96 bit nonce built from a 4 byte fixed field and a 64 bit big endian
+/// counter, `salt || counter`
+///
+/// As long as every counter value is only used once per key and salt, the nonces never repeat.
+pub fn derive_nonce(counter: u64, salt: &[u8; 4]) -> [u8; 12] {
+    let mut nonce = [0; 12];
+    nonce[..4].copy_from_slice(salt);
+    nonce[4..].copy_from_slice(&counter.to_be_bytes());
+    nonce
+}
+
+/// Hands out the nonces of `derive_nonce` for successive counter values and refuses to continue
+/// once the counter is exhausted instead of wrapping around
+#[derive(Debug)]
+pub struct NonceSequence {
+    salt: [u8; 4],
+    counter: Option<u64>,
+}
+
+impl NonceSequence {
+    pub fn new(salt: [u8; 4]) -> Self {
+        Self::with_counter(salt, 0)
+    }
+
+    /// Continue a sequence at `counter`, e.g. after persisting the counter
+    pub fn with_counter(salt: [u8; 4], counter: u64) -> Self {
+        Self {
+            salt,
+            counter: Some(counter),
+        }
+    }
+
+    /// Counter value of the next nonce, `None` if the sequence is exhausted
+    pub fn counter(&self) -> Option<u64> {
+        self.counter
+    }
+
+    pub fn next_nonce(&mut self) -> Result<ChaChaNonce, CryptoError> {
+        let counter = self.counter.ok_or(CryptoError::CounterExhausted)?;
+        self.counter = counter.checked_add(1);
+        Ok(ChaChaNonce::from_bytes(derive_nonce(counter, &self.salt)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn random() {
+        assert_ne!(Iv::random(), Iv::random());
+        assert_ne!(CtrNonce::random(), CtrNonce::random());
+        assert_ne!(ChaChaNonce::random(), ChaChaNonce::random());
+    }
+
+    #[test]
+    fn deref() {
+        let bytes = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15];
+
+        assert_eq!(*Iv::from_bytes(bytes), bytes);
+        assert_eq!(*CtrNonce::from_bytes(bytes), bytes);
+        assert_eq!(
+            *ChaChaNonce::from_bytes([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]),
+            bytes[0..12]
+        );
+    }
+
+    #[test]
+    fn derive_nonce_layout() {
+        assert_eq!(
+            derive_nonce(0x0102030405060708, &[0xa, 0xb, 0xc, 0xd]),
+            [0xa, 0xb, 0xc, 0xd, 1, 2, 3, 4, 5, 6, 7, 8]
+        );
+    }
+
+    #[test]
+    fn nonce_sequence() {
+        let mut seq = NonceSequence::new([1, 2, 3, 4]);
+        let a = seq.next_nonce().unwrap();
+        let b = seq.next_nonce().unwrap();
+        assert_ne!(a, b);
+        assert_eq!(*a, derive_nonce(0, &[1, 2, 3, 4]));
+        assert_eq!(*b, derive_nonce(1, &[1, 2, 3, 4]));
+        assert_eq!(seq.counter(), Some(2));
+
+        let mut seq = NonceSequence::with_counter([1, 2, 3, 4], u64::MAX - 1);
+        assert_eq!(
+            *seq.next_nonce().unwrap(),
+            derive_nonce(u64::MAX - 1, &[1, 2, 3, 4])
+        );
+        assert_eq!(
+            *seq.next_nonce().unwrap(),
+            derive_nonce(u64::MAX, &[1, 2, 3, 4])
+        );
+        assert_eq!(seq.counter(), None);
+        assert_eq!(seq.next_nonce(), Err(CryptoError::CounterExhausted));
+        assert_eq!(seq.next_nonce(), Err(CryptoError::CounterExhausted));
+    }
+}
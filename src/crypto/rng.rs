@@ -0,0 +1,51 @@
+/// Source of cryptographically secure random bytes
+pub trait Rng {
+    fn fill_bytes(&mut self, dest: &mut [u8]);
+}
+
+/// Random bytes from the operating system
+///
+/// Uses the `getrandom` crate if the `getrandom` feature is enabled and `/dev/urandom`
+/// otherwise
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OsRng;
+
+impl Rng for OsRng {
+    #[cfg(feature = "getrandom")]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        getrandom::getrandom(dest).expect("Can't get random bytes from the operating system");
+    }
+
+    #[cfg(not(feature = "getrandom"))]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        use std::io::Read;
+
+        let mut f = std::fs::File::open("/dev/urandom").expect("Can't open file /dev/urandom");
+        f.read_exact(dest)
+            .expect("Can't read from file /dev/urandom");
+    }
+}
+
+/// Fill `dest` with random bytes from `OsRng`
+pub fn fill_bytes(dest: &mut [u8]) {
+    OsRng.fill_bytes(dest);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fill_bytes_test() {
+        let mut a = [0; 32];
+        let mut b = [0; 32];
+        fill_bytes(&mut a);
+        OsRng.fill_bytes(&mut b);
+
+        assert_ne!(a, [0; 32]);
+        assert_ne!(a, b);
+
+        let mut empty = [];
+        fill_bytes(&mut empty);
+    }
+}
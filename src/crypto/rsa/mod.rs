@@ -0,0 +1,283 @@
+pub mod oaep;
+pub mod pkcs1v15;
+
+#[cfg(any(feature = "getrandom", all(feature = "std", unix)))]
+use crate::crypto::rng::Rng;
+use crate::math::bignum_fast::BignumFast;
+#[cfg(any(feature = "getrandom", all(feature = "std", unix)))]
+use alloc::vec;
+
+/// Fixed public exponent `e = 65537`
+pub const PUBLIC_EXPONENT: u32 = 65537;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RsaPublicKey<const N: usize> {
+    n: BignumFast<N>,
+    e: BignumFast<N>,
+}
+
+impl<const N: usize> RsaPublicKey<N> {
+    pub fn new(n: BignumFast<N>, e: BignumFast<N>) -> Self {
+        Self { n, e }
+    }
+
+    pub fn n(&self) -> &BignumFast<N> {
+        &self.n
+    }
+
+    pub fn e(&self) -> &BignumFast<N> {
+        &self.e
+    }
+
+    /// Length of the modulus in bytes
+    pub fn size(&self) -> usize {
+        self.n.bit_length().div_ceil(8)
+    }
+
+    /// Textbook RSA encryption `m^e mod n`
+    pub fn encrypt(&self, m: &BignumFast<N>) -> BignumFast<N> {
+        m.clone().pow_mod(self.e.clone(), &self.n)
+    }
+}
+
+/// `N` has to be at least twice the size of the modulus in bytes, so that products
+/// of two values mod n fit
+#[derive(Clone)]
+pub struct RsaPrivateKey<const N: usize> {
+    n: BignumFast<N>,
+    e: BignumFast<N>,
+    d: BignumFast<N>,
+    p: BignumFast<N>,
+    q: BignumFast<N>,
+    /// d mod (p - 1)
+    dp: BignumFast<N>,
+    /// d mod (q - 1)
+    dq: BignumFast<N>,
+    /// q^-1 mod p
+    qinv: BignumFast<N>,
+}
+
+impl<const N: usize> RsaPrivateKey<N> {
+    /// Generate a key with a `bits` bit modulus and `e = 65537`
+    ///
+    /// Panics unless `N` is at least twice the size of the modulus in bytes
+    #[cfg(any(feature = "getrandom", all(feature = "std", unix)))]
+    pub fn generate(bits: usize) -> Self {
+        assert!(bits >= 16, "RSA modulus has to be at least 16 bits");
+        assert!(
+            2 * bits.div_ceil(8) <= N,
+            "{} bytes are too small for a {} bit RSA key",
+            N,
+            bits
+        );
+
+        let e = BignumFast::from(PUBLIC_EXPONENT);
+
+        // e has to be invertible mod (p - 1)(q - 1)
+        let prime = |bits: usize| loop {
+            let p: BignumFast<N> = BignumFast::random_prime(bits);
+            if p.decrement_clone().gcd(&e).is_one() {
+                return p;
+            }
+        };
+
+        let p = prime(bits / 2);
+        let q = loop {
+            let q = prime(bits - bits / 2);
+            if q != p {
+                break q;
+            }
+        };
+
+        let n = p.mul_ref(&q);
+        let phi = p.decrement_clone().mul_ref(&q.decrement_clone());
+        let d = e
+            .mod_inverse(&phi)
+            .expect("e is coprime to p - 1 and q - 1");
+
+        let dp = d.rem_ref(&p.decrement_clone());
+        let dq = d.rem_ref(&q.decrement_clone());
+        let qinv = q.mod_inverse(&p).expect("p and q are distinct primes");
+
+        Self {
+            n,
+            e,
+            d,
+            p,
+            q,
+            dp,
+            dq,
+            qinv,
+        }
+    }
+
+    pub fn public_key(&self) -> RsaPublicKey<N> {
+        RsaPublicKey::new(self.n.clone(), self.e.clone())
+    }
+
+    pub fn n(&self) -> &BignumFast<N> {
+        &self.n
+    }
+
+    pub fn e(&self) -> &BignumFast<N> {
+        &self.e
+    }
+
+    pub fn d(&self) -> &BignumFast<N> {
+        &self.d
+    }
+
+    pub fn p(&self) -> &BignumFast<N> {
+        &self.p
+    }
+
+    pub fn q(&self) -> &BignumFast<N> {
+        &self.q
+    }
+
+    /// Length of the modulus in bytes
+    pub fn size(&self) -> usize {
+        self.n.bit_length().div_ceil(8)
+    }
+
+    /// Textbook RSA decryption `c^d mod n`
+    pub fn decrypt(&self, c: &BignumFast<N>) -> BignumFast<N> {
+        c.clone().pow_mod(self.d.clone(), &self.n)
+    }
+
+    /// RSA decryption using the Chinese remainder theorem (RFC 8017 - Section 5.1.2)
+    /// which works on half-size numbers and is roughly 3-4 times faster than `decrypt`
+    pub fn decrypt_crt(&self, c: &BignumFast<N>) -> BignumFast<N> {
+        let m1 = c.clone().pow_mod(self.dp.clone(), &self.p);
+        let m2 = c.clone().pow_mod(self.dq.clone(), &self.q);
+
+        // h = qinv * (m1 - m2) mod p
+        let h = self.qinv.mul_mod(&m1.sub_mod(&m2, &self.p), &self.p);
+
+        m2.add_ref(&h.mul_ref(&self.q))
+    }
+
+    /// `decrypt_crt` with base blinding: `m = (c * r^e)^d * r^-1 mod n` for a random `r`
+    /// coprime to `n`. The private exponentiation runs on a value the caller doesn't know,
+    /// which hides the timing and power profile of chosen ciphertexts.
+    #[cfg(any(feature = "getrandom", all(feature = "std", unix)))]
+    pub fn decrypt_blinded(&self, c: &BignumFast<N>, rng: &mut impl Rng) -> BignumFast<N> {
+        // 8 additional bytes make the bias of the reduction negligible
+        let mut buf = vec![0; (self.n.len() + 8).min(N)];
+        let (r, r_inv) = loop {
+            rng.fill_bytes(&mut buf);
+            let r = BignumFast::from_big_endian(&buf)
+                .expect("buffer fits into N bytes")
+                .rem_ref(&self.n);
+            if let Some(r_inv) = r.mod_inverse(&self.n) {
+                break (r, r_inv);
+            }
+        };
+
+        let blinded = c.mul_mod(&r.pow_mod(self.e.clone(), &self.n), &self.n);
+        self.decrypt_crt(&blinded).mul_mod(&r_inv, &self.n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate() {
+        let key: RsaPrivateKey<128> = RsaPrivateKey::generate(512);
+        let pub_key = key.public_key();
+
+        assert_eq!(key.n().bit_length(), 512);
+        assert_eq!(key.size(), 64);
+        assert_eq!(pub_key.size(), 64);
+        assert_eq!(key.p.bit_length(), 256);
+        assert_eq!(key.q.bit_length(), 256);
+        assert_eq!(key.p.mul_ref(&key.q), *key.n());
+        assert_eq!(*key.e(), BignumFast::from(PUBLIC_EXPONENT));
+
+        let phi = key.p.decrement_clone().mul_ref(&key.q.decrement_clone());
+        assert_eq!(key.e().mul_mod(key.d(), &phi), BignumFast::from(1u32));
+
+        let m = BignumFast::try_from_hex_string("0x414141414141414141").unwrap();
+        let c = pub_key.encrypt(&m);
+        assert_ne!(c, m);
+        assert_eq!(key.decrypt(&c), m);
+    }
+
+    #[test]
+    fn encrypt_decrypt_small_keys() {
+        for bits in [16, 33, 64, 100] {
+            let key: RsaPrivateKey<32> = RsaPrivateKey::generate(bits);
+            assert_eq!(key.n().bit_length(), bits);
+
+            for m in [0u32, 1, 2, 0x7fff] {
+                let m = BignumFast::from(m);
+                assert_eq!(key.decrypt(&key.public_key().encrypt(&m)), m);
+            }
+        }
+    }
+
+    #[test]
+    fn decrypt_crt() {
+        let key: RsaPrivateKey<64> = RsaPrivateKey::generate(256);
+
+        assert_eq!(key.dp, key.d().clone() % key.p().decrement_clone());
+        assert_eq!(key.qinv.mul_mod(key.q(), key.p()), BignumFast::from(1u32));
+
+        for _ in 0..5 {
+            let c = BignumFast::rand() % key.n().clone();
+            assert_eq!(key.decrypt_crt(&c), key.decrypt(&c));
+        }
+
+        for m in [0u32, 1, 2, 0xabcdef] {
+            let m = BignumFast::from(m);
+            let c = key.public_key().encrypt(&m);
+            assert_eq!(key.decrypt_crt(&c), m);
+        }
+    }
+
+    #[test]
+    fn decrypt_blinded() {
+        use crate::crypto::rng::OsRng;
+
+        /// Counts up, so that small and non-coprime blinds show up as well
+        struct CountingRng(u8);
+
+        impl Rng for CountingRng {
+            fn fill_bytes(&mut self, dest: &mut [u8]) {
+                dest.fill(0);
+                self.0 = self.0.wrapping_add(1);
+                *dest.last_mut().unwrap() = self.0;
+            }
+        }
+
+        let key: RsaPrivateKey<64> = RsaPrivateKey::generate(256);
+        let mut counting = CountingRng(0);
+
+        for _ in 0..5 {
+            let c = BignumFast::rand() % key.n().clone();
+            let m = key.decrypt(&c);
+            assert_eq!(key.decrypt_blinded(&c, &mut OsRng), m);
+            assert_eq!(key.decrypt_blinded(&c, &mut counting), m);
+        }
+
+        // r = 0 has no inverse and is skipped
+        let m = BignumFast::from(0x1234u32);
+        let c = key.public_key().encrypt(&m);
+        assert_eq!(key.decrypt_blinded(&c, &mut CountingRng(255)), m);
+    }
+
+    #[test]
+    #[should_panic]
+    fn generate_too_large() {
+        let _: RsaPrivateKey<16> = RsaPrivateKey::generate(128);
+    }
+
+    #[test]
+    #[should_panic(expected = "too small")]
+    fn generate_modulus_not_byte_aligned() {
+        // 2 * 65 bits fit into 17 bytes, but the 9 byte modulus needs 18 for its squares
+        let _: RsaPrivateKey<17> = RsaPrivateKey::generate(65);
+    }
+}
@@ -0,0 +1,347 @@
+use super::RsaPrivateKey;
+#[cfg(any(feature = "getrandom", all(feature = "std", unix)))]
+use super::RsaPublicKey;
+#[cfg(any(feature = "getrandom", all(feature = "std", unix)))]
+use crate::crypto::rng::Rng;
+use crate::crypto::{digest::Digest, error::CryptoError};
+use crate::math::bignum_fast::BignumFast;
+use crate::util::{ct, zeroize};
+#[cfg(any(feature = "getrandom", all(feature = "std", unix)))]
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// MGF1 (RFC 8017 - Appendix B.2.1)
+///
+/// `T = Hash(seed || C(0)) || Hash(seed || C(1)) || ...` truncated to `len` bytes, with `C(i)`
+/// the counter as 4 byte big endian integer
+pub fn mgf1<D: Digest>(seed: &[u8], len: usize) -> Vec<u8> {
+    let mut mask = Vec::with_capacity(len + D::OUTPUT_SIZE);
+    let mut counter = 0u32;
+    while mask.len() < len {
+        let mut h = D::new();
+        h.update(seed);
+        h.update(&counter.to_be_bytes());
+        mask.extend(h.finalize());
+        counter += 1;
+    }
+    mask.truncate(len);
+    mask
+}
+
+fn xor_mask(data: &mut [u8], mask: &[u8]) {
+    for (d, m) in data.iter_mut().zip(mask) {
+        *d ^= m;
+    }
+}
+
+/// EME-OAEP encoding (RFC 8017 - Section 7.1.1 step 2)
+///
+/// `EM = 0x00 || maskedSeed || maskedDB` with `DB = lHash || PS || 0x01 || M`
+#[cfg(any(feature = "getrandom", all(feature = "std", unix)))]
+fn encode<D: Digest>(k: usize, label: &[u8], msg: &[u8], seed: &[u8]) -> Vec<u8> {
+    let h_len = D::OUTPUT_SIZE;
+
+    let mut db = D::digest(label);
+    db.resize(k - msg.len() - h_len - 2, 0);
+    db.push(0x01);
+    db.extend_from_slice(msg);
+    let db_mask = mgf1::<D>(seed, db.len());
+    xor_mask(&mut db, &db_mask);
+
+    let mut masked_seed = seed.to_vec();
+    xor_mask(&mut masked_seed, &mgf1::<D>(&db, h_len));
+
+    let mut em = vec![0x00];
+    em.extend(masked_seed);
+    em.extend(db);
+    em
+}
+
+/// EME-OAEP decoding (RFC 8017 - Section 7.1.2 step 3)
+///
+/// All checks are accumulated without branching on the decoded data and fail with the same
+/// error, so that neither the timing nor the result tells which check failed (Manger's attack)
+fn decode<D: Digest>(label: &[u8], em: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let h_len = D::OUTPUT_SIZE;
+    let (masked_seed, masked_db) = em[1..].split_at(h_len);
+
+    let mut seed = masked_seed.to_vec();
+    xor_mask(&mut seed, &mgf1::<D>(masked_db, h_len));
+    let mut db = masked_db.to_vec();
+    xor_mask(&mut db, &mgf1::<D>(&seed, masked_db.len()));
+    zeroize(&mut seed);
+
+    let mut bad = em[0] | (!ct::bytes_eq(&db[..h_len], &D::digest(label)) as u8);
+
+    // PS || 0x01 || M: the separator is the first non-zero byte and has to be 0x01
+    let mut found = 0u8;
+    let mut separator = 0usize;
+    for (i, b) in db[h_len..].iter().enumerate() {
+        let is_zero = ct::zero_mask(*b);
+        let is_one = ct::zero_mask(*b ^ 0x01);
+
+        let first_one = is_one & !found;
+        separator |= i & ((first_one & 1) as usize).wrapping_neg();
+        bad |= !found & !is_zero & !is_one;
+        found |= is_one;
+    }
+    bad |= !found;
+
+    if core::hint::black_box(bad) != 0 {
+        zeroize(&mut db);
+        return Err(CryptoError::DecryptionFailed);
+    }
+
+    let msg = db[h_len + separator + 1..].to_vec();
+    zeroize(&mut db);
+    Ok(msg)
+}
+
+/// RSAES-OAEP encryption (RFC 8017 - Section 7.1.1) with MGF1 over `D`
+///
+/// The message can be at most `k - 2 * hLen - 2` bytes long, with `k` the size of the modulus
+#[cfg(any(feature = "getrandom", all(feature = "std", unix)))]
+pub fn encrypt<D: Digest, const N: usize>(
+    pub_key: &RsaPublicKey<N>,
+    label: &[u8],
+    msg: &[u8],
+    rng: &mut impl Rng,
+) -> Result<Vec<u8>, CryptoError> {
+    let k = pub_key.size();
+    let h_len = D::OUTPUT_SIZE;
+    if k < 2 * h_len + 2 || msg.len() > k - 2 * h_len - 2 {
+        return Err(CryptoError::MessageTooLong);
+    }
+
+    let mut seed = vec![0; h_len];
+    rng.fill_bytes(&mut seed);
+    let mut em = encode::<D>(k, label, msg, &seed);
+    zeroize(&mut seed);
+
+    let m = BignumFast::from_big_endian(&em).ok_or(CryptoError::InvalidKey)?;
+    zeroize(&mut em);
+    let c = pub_key.encrypt(&m);
+    Ok(c.to_fixed_big_endian(k).expect("c < n fits into k bytes"))
+}
+
+/// RSAES-OAEP decryption (RFC 8017 - Section 7.1.2) with MGF1 over `D`
+///
+/// `label` has to be the one used for encryption. All failures return the same error to not
+/// act as a padding oracle
+pub fn decrypt<D: Digest, const N: usize>(
+    priv_key: &RsaPrivateKey<N>,
+    label: &[u8],
+    ciphertext: &[u8],
+) -> Result<Vec<u8>, CryptoError> {
+    let k = priv_key.size();
+    let err = CryptoError::DecryptionFailed;
+
+    if k < 2 * D::OUTPUT_SIZE + 2 || ciphertext.len() != k {
+        return Err(err);
+    }
+
+    let c = BignumFast::from_big_endian(ciphertext).ok_or(err)?;
+    if c >= *priv_key.n() {
+        return Err(err);
+    }
+
+    let mut em = priv_key.decrypt_crt(&c).to_fixed_big_endian(k).ok_or(err)?;
+    let msg = decode::<D>(label, &em);
+    zeroize(&mut em);
+    msg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::rng::OsRng;
+    use crate::crypto::sha3::{Sha3_256, Sha3_512};
+    use crate::util::hex;
+
+    /// Always returns the same bytes, to get a fixed seed
+    struct FixedRng(Vec<u8>);
+
+    impl Rng for FixedRng {
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            dest.copy_from_slice(&self.0[..dest.len()]);
+        }
+    }
+
+    /// 1024 bit key of the `bignumfast_rsa` example
+    fn fixed_key() -> RsaPrivateKey<256> {
+        let p = BignumFast::try_from_hex_string("0xd00e8de65f7c32094b732a5628cefedc35ed796b7cea6297614545df71d8dbc67ea14565534bfc9bc5f1a680239227189c2d493924a5bd64641169533201d6e5").unwrap();
+        let q = BignumFast::try_from_hex_string("0xcd3ac881cc47aa776a9829c0e529e3d0dbb5a43c366842578341a051dafaf4f6164f2dc0e72a3bd3b33f8b2f84a6cf35f0781e7c466e677ff8e553de5c92c617").unwrap();
+        let d = BignumFast::try_from_hex_string("0x8367e1ba7e06c57060c8fbebccb8b033a3c8105b30d7dc31b2e7d1e97dae1ec75b4f5fb0f9f3c9c160fe257d68d74495eea80c0af838f37c9db7a24558c21e28c49d57470b002d90a383caebb5821a59583d15502f0012c9235f806c62c97f1e3cafbc72118fcf60743168125801e06cc7293cde64d241339aad516e7bcc1081").unwrap();
+
+        RsaPrivateKey {
+            n: p.mul_ref(&q),
+            e: BignumFast::from(super::super::PUBLIC_EXPONENT),
+            dp: d.rem_ref(&p.decrement_clone()),
+            dq: d.rem_ref(&q.decrement_clone()),
+            qinv: q.mod_inverse(&p).unwrap(),
+            d,
+            p,
+            q,
+        }
+    }
+
+    #[test]
+    fn mgf1_sha3_256() {
+        // computed with Python's hashlib.sha3_256
+        assert_eq!(
+            mgf1::<Sha3_256>(b"seed", 100),
+            hex::decode(
+                "7fe2e122219eb3f5a8b31f3bab2fa73c7fe7cf915da2b28692a7630a7dd9cd99\
+                 0100e302da9014519654dc979034a12ea9e08423477d1b3cddb0fe840d3c62ae\
+                 9f545298a0f8ed341dd47c2fa1c9edf8516a0cf1cc66b67c7ce42916f72bd436\
+                 40a46dd9"
+            )
+            .unwrap()
+        );
+        assert!(mgf1::<Sha3_256>(b"seed", 0).is_empty());
+        assert_eq!(
+            mgf1::<Sha3_256>(b"seed", 32),
+            Sha3_256::digest(b"seed\0\0\0\0")
+        );
+    }
+
+    #[test]
+    fn fixed_seed_vector() {
+        // EM and c = EM^e mod n computed independently with Python (hashlib.sha3_256)
+        let key = fixed_key();
+        let label = b"nikrypt";
+        let msg = b"attack at dawn";
+        let seed: Vec<u8> = (0..32).collect();
+        let em = hex::decode(
+            "005dd5d21b0c83fe48d8a636980287b6411b22d066b781c6869db85be69c44b7\
+             31b1cda1141691768f4a7cb00e8c31552c87e71f5ad1bcf05d3ee37da27124f5\
+             8ca9e1496db2ee7ad87197cf3d0a09a0de43e6637920af7668174424c406d42a\
+             2b41640787d400dd54597565ae48a81710d2e0ab1a4bab19d3436e5c713c4982",
+        )
+        .unwrap();
+        let c = hex::decode(
+            "8a8eb8014f2f69da5a1d64a7eaad09542e842d0aacea36deadc6303ebb39ad4a\
+             fd4dab46433dbddf14dc9ee1e3064ce605e7d92958c4a81b3112d2ebdfe01a17\
+             0e0298f37d378b8ed387b70b0e1b630e241129be11c96eeada716cfebe6cb24b\
+             9f334d3afafb22aeca1de318d7fe2ccc79099b49b1783bb3081e12398a9e5abd",
+        )
+        .unwrap();
+
+        assert_eq!(encode::<Sha3_256>(128, label, msg, &seed), em);
+        assert_eq!(decode::<Sha3_256>(label, &em).unwrap(), msg);
+        assert_eq!(
+            encrypt::<Sha3_256, 256>(&key.public_key(), label, msg, &mut FixedRng(seed)).unwrap(),
+            c
+        );
+        assert_eq!(decrypt::<Sha3_256, 256>(&key, label, &c).unwrap(), msg);
+    }
+
+    #[test]
+    fn encrypt_decrypt() {
+        let key = fixed_key();
+        let k = key.size();
+
+        // SHA3-256 leaves k - 66 bytes for the message
+        for len in [0, 17, k - 66] {
+            let msg: Vec<u8> = (0..len as u8).collect();
+            for label in [&b""[..], b"label"] {
+                let c =
+                    encrypt::<Sha3_256, 256>(&key.public_key(), label, &msg, &mut OsRng).unwrap();
+                assert_eq!(c.len(), k);
+                assert_eq!(decrypt::<Sha3_256, 256>(&key, label, &c).unwrap(), msg);
+            }
+        }
+        assert_eq!(
+            encrypt::<Sha3_256, 256>(&key.public_key(), b"", &vec![0; k - 65], &mut OsRng),
+            Err(CryptoError::MessageTooLong)
+        );
+
+        // SHA3-512 needs k >= 2 * 64 + 2, one byte more than the 1024 bit key has
+        assert_eq!(
+            encrypt::<Sha3_512, 256>(&key.public_key(), b"", b"", &mut OsRng),
+            Err(CryptoError::MessageTooLong)
+        );
+        assert_eq!(
+            decrypt::<Sha3_512, 256>(&key, b"", &vec![0; k]),
+            Err(CryptoError::DecryptionFailed)
+        );
+    }
+
+    #[test]
+    fn decrypt_malformed() {
+        let key = fixed_key();
+        let k = key.size();
+        let label = b"nikrypt";
+        let seed: Vec<u8> = (0..32).collect();
+        let valid = encode::<Sha3_256>(k, label, b"attack at dawn", &seed);
+
+        let raw_encrypt = |em: &[u8]| {
+            key.public_key()
+                .encrypt(&BignumFast::from_big_endian(em).unwrap())
+                .to_fixed_big_endian(k)
+                .unwrap()
+        };
+        assert!(decrypt::<Sha3_256, 256>(&key, label, &raw_encrypt(&valid)).is_ok());
+
+        // wrong label
+        assert_eq!(
+            decrypt::<Sha3_256, 256>(&key, b"other", &raw_encrypt(&valid)),
+            Err(CryptoError::DecryptionFailed)
+        );
+
+        // leading byte not zero
+        let mut em = valid.clone();
+        em[0] = 0x01;
+        assert_eq!(
+            decrypt::<Sha3_256, 256>(&key, label, &raw_encrypt(&em)),
+            Err(CryptoError::DecryptionFailed)
+        );
+
+        // any change of the masked seed or DB breaks the label hash or the separator
+        for i in [1, 33, 127] {
+            let mut em = valid.clone();
+            em[i] ^= 0x10;
+            assert_eq!(
+                decrypt::<Sha3_256, 256>(&key, label, &raw_encrypt(&em)),
+                Err(CryptoError::DecryptionFailed)
+            );
+        }
+
+        // DB with a non-zero byte before the separator and DB without a separator
+        let build = |db_tail: &[u8]| {
+            let mut db = Sha3_256::digest(label);
+            db.extend_from_slice(db_tail);
+            let mut masked_db = db.clone();
+            xor_mask(&mut masked_db, &mgf1::<Sha3_256>(&seed, db.len()));
+            let mut masked_seed = seed.clone();
+            xor_mask(&mut masked_seed, &mgf1::<Sha3_256>(&masked_db, 32));
+
+            let mut em = vec![0x00];
+            em.extend(masked_seed);
+            em.extend(masked_db);
+            em
+        };
+        let mut tail = vec![0; k - 65];
+        tail[k - 66] = 0x01;
+        assert_eq!(decode::<Sha3_256>(label, &build(&tail)).unwrap(), b"");
+        tail[3] = 0x02;
+        assert_eq!(
+            decode::<Sha3_256>(label, &build(&tail)),
+            Err(CryptoError::DecryptionFailed)
+        );
+        assert_eq!(
+            decode::<Sha3_256>(label, &build(&vec![0; k - 65])),
+            Err(CryptoError::DecryptionFailed)
+        );
+
+        // wrong ciphertext length and c >= n
+        assert_eq!(
+            decrypt::<Sha3_256, 256>(&key, label, &raw_encrypt(&valid)[1..]),
+            Err(CryptoError::DecryptionFailed)
+        );
+        assert_eq!(
+            decrypt::<Sha3_256, 256>(&key, label, &vec![0xff; k]),
+            Err(CryptoError::DecryptionFailed)
+        );
+    }
+}
@@ -0,0 +1,213 @@
+use super::RsaPrivateKey;
+#[cfg(any(feature = "getrandom", all(feature = "std", unix)))]
+use super::RsaPublicKey;
+use crate::crypto::error::CryptoError;
+#[cfg(any(feature = "getrandom", all(feature = "std", unix)))]
+use crate::crypto::rng::Rng;
+use crate::math::bignum_fast::BignumFast;
+use crate::util::{ct, zeroize};
+#[cfg(any(feature = "getrandom", all(feature = "std", unix)))]
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// RSAES-PKCS1-v1_5 encryption (RFC 8017 - Section 7.2.1)
+///
+/// `EM = 0x00 || 0x02 || PS || 0x00 || M` with at least 8 nonzero random padding bytes
+#[cfg(any(feature = "getrandom", all(feature = "std", unix)))]
+pub fn encrypt<const N: usize>(
+    pub_key: &RsaPublicKey<N>,
+    rng: &mut impl Rng,
+    msg: &[u8],
+) -> Result<Vec<u8>, CryptoError> {
+    let k = pub_key.size();
+    if k < 11 || msg.len() > k - 11 {
+        return Err(CryptoError::MessageTooLong);
+    }
+
+    let mut ps = vec![0; k - msg.len() - 3];
+    rng.fill_bytes(&mut ps);
+    for b in ps.iter_mut() {
+        while *b == 0 {
+            let mut buf = [0];
+            rng.fill_bytes(&mut buf);
+            *b = buf[0];
+        }
+    }
+
+    let mut em = vec![0x00, 0x02];
+    em.extend(ps);
+    em.push(0x00);
+    em.extend(msg);
+
+    let m = BignumFast::from_big_endian(&em).ok_or(CryptoError::InvalidKey)?;
+    let c = pub_key.encrypt(&m);
+    Ok(c.to_fixed_big_endian(k).expect("c < n fits into k bytes"))
+}
+
+/// RSAES-PKCS1-v1_5 decryption (RFC 8017 - Section 7.2.2)
+///
+/// All padding checks are accumulated without branching on the decoded data and fail with the
+/// same error, so that neither the timing nor the result acts as a padding oracle (Bleichenbacher)
+pub fn decrypt<const N: usize>(
+    priv_key: &RsaPrivateKey<N>,
+    ciphertext: &[u8],
+) -> Result<Vec<u8>, CryptoError> {
+    let k = priv_key.size();
+    let err = CryptoError::DecryptionFailed;
+
+    if k < 11 || ciphertext.len() != k {
+        return Err(err);
+    }
+
+    let c = BignumFast::from_big_endian(ciphertext).ok_or(err)?;
+    if c >= *priv_key.n() {
+        return Err(err);
+    }
+
+    let mut em = priv_key.decrypt_crt(&c).to_fixed_big_endian(k).ok_or(err)?;
+
+    // 0x00 || 0x02 || PS || 0x00 || M: the separator is the first zero byte after the block
+    // type and at least 8 padding bytes have to come before it
+    let mut bad = em[0] | (em[1] ^ 0x02);
+    let mut found = 0u8;
+    let mut separator = 0usize;
+    for (i, b) in em.iter().enumerate().skip(2) {
+        let first_zero = ct::zero_mask(*b) & !found;
+        separator |= i & ((first_zero & 1) as usize).wrapping_neg();
+        found |= first_zero;
+    }
+    bad |= !found;
+    bad |= (separator < 10) as u8;
+
+    if core::hint::black_box(bad) != 0 {
+        zeroize(&mut em);
+        return Err(err);
+    }
+
+    let msg = em[separator + 1..].to_vec();
+    zeroize(&mut em);
+    Ok(msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::rng::OsRng;
+
+    /// Returns a lot of zero bytes to exercise the resampling of the padding
+    struct ZeroHeavyRng(u8);
+
+    impl Rng for ZeroHeavyRng {
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            for b in dest.iter_mut() {
+                self.0 = self.0.wrapping_add(1);
+                *b = if self.0.is_multiple_of(3) { self.0 } else { 0 };
+            }
+        }
+    }
+
+    #[test]
+    fn encrypt_decrypt() {
+        let key: RsaPrivateKey<64> = RsaPrivateKey::generate(256);
+        let k = key.size();
+
+        for len in [0, 1, 5, k - 11] {
+            let msg: Vec<u8> = (0..len as u8).collect();
+
+            let c = encrypt(&key.public_key(), &mut OsRng, &msg).unwrap();
+            assert_eq!(c.len(), k);
+            assert_eq!(decrypt(&key, &c).unwrap(), msg);
+
+            let c = encrypt(&key.public_key(), &mut ZeroHeavyRng(0), &msg).unwrap();
+            assert_eq!(decrypt(&key, &c).unwrap(), msg);
+
+            // padding is nonzero and followed by the separator
+            let em = key
+                .decrypt(&BignumFast::from_big_endian(&c).unwrap())
+                .to_fixed_big_endian(k)
+                .unwrap();
+            assert_eq!(em[0..2], [0x00, 0x02]);
+            assert!(em[2..k - len - 1].iter().all(|b| *b != 0));
+            assert_eq!(em[k - len - 1], 0x00);
+        }
+
+        let msg = vec![0x41; k - 10];
+        assert_eq!(
+            encrypt(&key.public_key(), &mut OsRng, &msg),
+            Err(CryptoError::MessageTooLong)
+        );
+    }
+
+    #[test]
+    fn decrypt_malformed() {
+        let key: RsaPrivateKey<64> = RsaPrivateKey::generate(256);
+        let k = key.size();
+
+        let raw_encrypt = |em: &[u8]| {
+            key.public_key()
+                .encrypt(&BignumFast::from_big_endian(em).unwrap())
+                .to_fixed_big_endian(k)
+                .unwrap()
+        };
+
+        let mut valid = vec![0x00, 0x02];
+        valid.extend(vec![0xff; 10]);
+        valid.push(0x00);
+        valid.extend(vec![0x41; k - 13]);
+        assert_eq!(
+            decrypt(&key, &raw_encrypt(&valid)).unwrap(),
+            vec![0x41; k - 13]
+        );
+
+        // exactly 8 padding bytes and zeros in the message after the separator
+        let mut em = vec![0x00, 0x02];
+        em.extend(vec![0xff; 8]);
+        em.push(0x00);
+        em.extend(vec![0x00; k - 11]);
+        assert_eq!(
+            decrypt(&key, &raw_encrypt(&em)).unwrap(),
+            vec![0x00; k - 11]
+        );
+
+        // wrong block type
+        let mut em = valid.clone();
+        em[1] = 0x01;
+        assert_eq!(
+            decrypt(&key, &raw_encrypt(&em)),
+            Err(CryptoError::DecryptionFailed)
+        );
+
+        // leading byte not zero
+        let mut em = valid.clone();
+        em[0] = 0x01;
+        assert_eq!(
+            decrypt(&key, &raw_encrypt(&em)),
+            Err(CryptoError::DecryptionFailed)
+        );
+
+        // padding shorter than 8 bytes
+        let mut em = valid.clone();
+        em[9] = 0x00;
+        assert_eq!(
+            decrypt(&key, &raw_encrypt(&em)),
+            Err(CryptoError::DecryptionFailed)
+        );
+
+        // no separator
+        let em: Vec<u8> = [0x00, 0x02].into_iter().chain(vec![0xff; k - 2]).collect();
+        assert_eq!(
+            decrypt(&key, &raw_encrypt(&em)),
+            Err(CryptoError::DecryptionFailed)
+        );
+
+        // wrong ciphertext length and c >= n
+        assert_eq!(
+            decrypt(&key, &raw_encrypt(&valid)[1..]),
+            Err(CryptoError::DecryptionFailed)
+        );
+        assert_eq!(
+            decrypt(&key, &vec![0xff; k]),
+            Err(CryptoError::DecryptionFailed)
+        );
+    }
+}
@@ -0,0 +1,85 @@
+/// FIPS 202 - Section 3.2.5 - round constants for ι
+const ROUND_CONSTANTS: [u64; 24] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808a,
+    0x8000000080008000,
+    0x000000000000808b,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008a,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000a,
+    0x000000008000808b,
+    0x800000000000008b,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800a,
+    0x800000008000000a,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+/// FIPS 202 - Section 3.2.2 - rotation offsets for ρ, indexed by `x + 5 * y`
+const ROTATION_OFFSETS: [u32; 25] = [
+    0, 1, 62, 28, 27, 36, 44, 6, 55, 20, 3, 10, 43, 25, 39, 41, 45, 15, 21, 8, 18, 2, 61, 56, 14,
+];
+
+/// FIPS 202 - Section 3.3 - Keccak-f[1600] on a 5x5 lane state, lane `(x, y)` is `state[x + 5 * y]`
+pub fn keccak_f1600(state: &mut [u64; 25]) {
+    for rc in ROUND_CONSTANTS {
+        // θ
+        let mut c = [0u64; 5];
+        for x in 0..5 {
+            c[x] = state[x] ^ state[x + 5] ^ state[x + 10] ^ state[x + 15] ^ state[x + 20];
+        }
+        for x in 0..5 {
+            let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+            for y in 0..5 {
+                state[x + 5 * y] ^= d;
+            }
+        }
+
+        // ρ and π: B[y, 2x + 3y] = rot(A[x, y], r[x, y])
+        let mut b = [0u64; 25];
+        for x in 0..5 {
+            for y in 0..5 {
+                let i = x + 5 * y;
+                b[y + 5 * ((2 * x + 3 * y) % 5)] = state[i].rotate_left(ROTATION_OFFSETS[i]);
+            }
+        }
+
+        // χ
+        for x in 0..5 {
+            for y in 0..5 {
+                state[x + 5 * y] =
+                    b[x + 5 * y] ^ (!b[(x + 1) % 5 + 5 * y] & b[(x + 2) % 5 + 5 * y]);
+            }
+        }
+
+        // ι
+        state[0] ^= rc;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keccak_f1600_zero_state() {
+        // Keccak team reference (KeccakF-1600-IntermediateValues.txt), first permutation of zero
+        let mut state = [0u64; 25];
+        keccak_f1600(&mut state);
+
+        assert_eq!(state[0], 0xf1258f7940e1dde7);
+        assert_eq!(state[1], 0x84d5ccf933c0478a);
+        assert_eq!(state[24], 0xeaf1ff7b5ceca249);
+    }
+}
@@ -0,0 +1,251 @@
+mod keccak;
+
+use self::keccak::keccak_f1600;
+use super::digest::Digest;
+use alloc::vec::Vec;
+
+/// FIPS 202 - Section 4 - sponge construction over Keccak-f[1600]
+///
+/// `RATE` is the rate in bytes, the capacity is `200 - RATE` bytes
+#[derive(Clone)]
+struct Sponge<const RATE: usize> {
+    state: [u64; 25],
+    buffer: [u8; RATE],
+    buffer_len: usize,
+}
+
+impl<const RATE: usize> Sponge<RATE> {
+    fn new() -> Self {
+        Self {
+            state: [0; 25],
+            buffer: [0; RATE],
+            buffer_len: 0,
+        }
+    }
+
+    /// XOR a full block into the first `RATE` bytes of the state and permute
+    fn absorb_block(state: &mut [u64; 25], block: &[u8; RATE]) {
+        for (lane, chunk) in state.iter_mut().zip(block.chunks_exact(8)) {
+            *lane ^= u64::from_le_bytes(chunk.try_into().unwrap());
+        }
+        keccak_f1600(state);
+    }
+
+    fn update(&mut self, mut data: &[u8]) {
+        if self.buffer_len > 0 {
+            let n = (RATE - self.buffer_len).min(data.len());
+            self.buffer[self.buffer_len..self.buffer_len + n].copy_from_slice(&data[..n]);
+            self.buffer_len += n;
+            data = &data[n..];
+
+            if self.buffer_len < RATE {
+                return;
+            }
+            Self::absorb_block(&mut self.state, &self.buffer);
+            self.buffer_len = 0;
+        }
+
+        let mut blocks = data.chunks_exact(RATE);
+        for block in &mut blocks {
+            Self::absorb_block(&mut self.state, block.try_into().unwrap());
+        }
+
+        let rest = blocks.remainder();
+        self.buffer[..rest.len()].copy_from_slice(rest);
+        self.buffer_len = rest.len();
+    }
+
+    /// Pad with the SHA-3 domain separation bits `01` followed by `pad10*1`
+    /// (FIPS 202 - Section 6.1 and Section 5.1) and squeeze `out.len() <= RATE` bytes
+    fn finalize(mut self, out: &mut [u8]) {
+        self.buffer[self.buffer_len..].fill(0);
+        self.buffer[self.buffer_len] ^= 0x06;
+        self.buffer[RATE - 1] ^= 0x80;
+        Self::absorb_block(&mut self.state, &self.buffer);
+
+        for (chunk, lane) in out.chunks_mut(8).zip(self.state) {
+            chunk.copy_from_slice(&lane.to_le_bytes()[..chunk.len()]);
+        }
+    }
+}
+
+/// SHA3-256 (FIPS 202 - Section 6.1), rate of 1088 bits
+#[derive(Clone)]
+pub struct Sha3_256 {
+    sponge: Sponge<136>,
+}
+
+impl Sha3_256 {
+    pub fn new() -> Self {
+        Self {
+            sponge: Sponge::new(),
+        }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.sponge.update(data);
+    }
+
+    pub fn finalize(self) -> [u8; 32] {
+        let mut out = [0; 32];
+        self.sponge.finalize(&mut out);
+        out
+    }
+}
+
+impl Default for Sha3_256 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Digest for Sha3_256 {
+    const BLOCK_SIZE: usize = 136;
+    const OUTPUT_SIZE: usize = 32;
+
+    fn new() -> Self {
+        Self::new()
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        self.update(data);
+    }
+
+    fn finalize(self) -> Vec<u8> {
+        self.finalize().to_vec()
+    }
+}
+
+/// SHA3-512 (FIPS 202 - Section 6.1), rate of 576 bits
+#[derive(Clone)]
+pub struct Sha3_512 {
+    sponge: Sponge<72>,
+}
+
+impl Sha3_512 {
+    pub fn new() -> Self {
+        Self {
+            sponge: Sponge::new(),
+        }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.sponge.update(data);
+    }
+
+    pub fn finalize(self) -> [u8; 64] {
+        let mut out = [0; 64];
+        self.sponge.finalize(&mut out);
+        out
+    }
+}
+
+impl Default for Sha3_512 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Digest for Sha3_512 {
+    const BLOCK_SIZE: usize = 72;
+    const OUTPUT_SIZE: usize = 64;
+
+    fn new() -> Self {
+        Self::new()
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        self.update(data);
+    }
+
+    fn finalize(self) -> Vec<u8> {
+        self.finalize().to_vec()
+    }
+}
+
+/// SHA3-256 digest of `data` in one call
+pub fn sha3_256(data: &[u8]) -> [u8; 32] {
+    let mut h = Sha3_256::new();
+    h.update(data);
+    h.finalize()
+}
+
+/// SHA3-512 digest of `data` in one call
+pub fn sha3_512(data: &[u8]) -> [u8; 64] {
+    let mut h = Sha3_512::new();
+    h.update(data);
+    h.finalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::hex;
+
+    #[test]
+    fn sha3_256_test_vectors() {
+        // NIST FIPS 202 example values for the empty message and "abc"
+        assert_eq!(
+            sha3_256(b"").to_vec(),
+            hex::decode("a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a")
+                .unwrap()
+        );
+        assert_eq!(
+            sha3_256(b"abc").to_vec(),
+            hex::decode("3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn sha3_512_test_vectors() {
+        assert_eq!(
+            sha3_512(b"").to_vec(),
+            hex::decode(
+                "a69f73cca23a9ac5c8b567dc185a756e97c982164fe25859e0d1dcc1475c80a6\
+                 15b2123af1f5f94c11e3e9402c3ac558f500199d95b6d3e301758586281dcd26"
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            sha3_512(b"abc").to_vec(),
+            hex::decode(
+                "b751850b1a57168a5693cd924b6b096e08f621827444f70d884f5d0240d2712e\
+                 10e116e9192af3c91a7ec57647e3934057340b4cf408d5a56592f8274eec53f0"
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn multi_block_and_incremental() {
+        // 200 bytes span more than one block for both rates
+        let msg = [b'a'; 200];
+        assert_eq!(
+            sha3_256(&msg).to_vec(),
+            hex::decode("cce34485baf2bf2aca99b94833892a4f52896d3d153f7b840cc4f9fe695f1387")
+                .unwrap()
+        );
+        assert_eq!(
+            sha3_512(&msg).to_vec(),
+            hex::decode(
+                "eae6c85c6904f11075de9f9d5e1064371d000510fa3d2d79d40cf9be34892fb0\
+                 1859d0a0234e138bcb0ad5c84f6c0dca226a414b0c9a2897cb695f5185fe36ec"
+            )
+            .unwrap()
+        );
+
+        for split in [0, 1, 71, 72, 135, 136, 137, 200] {
+            let mut h = Sha3_256::new();
+            h.update(&msg[..split]);
+            h.update(&msg[split..]);
+            assert_eq!(h.finalize(), sha3_256(&msg));
+
+            let mut h = Sha3_512::new();
+            for chunk in msg.chunks(split.max(1)) {
+                h.update(chunk);
+            }
+            assert_eq!(h.finalize(), sha3_512(&msg));
+        }
+    }
+}
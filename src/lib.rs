@@ -1,2 +1,7 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
 pub mod crypto;
 pub mod math;
+pub mod util;
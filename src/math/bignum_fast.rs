@@ -1,19 +1,78 @@
+#[cfg(any(feature = "getrandom", all(feature = "std", unix)))]
+use crate::crypto::rng;
+use crate::util::zeroize;
+use alloc::{format, string::String, string::ToString, vec, vec::Vec};
+
+#[cfg(any(feature = "getrandom", all(feature = "std", unix)))]
+use super::primes::small_primes;
+use super::utils;
+
+/// Miller-Rabin rounds for an error probability below 2^-80 on random candidates
+/// (Handbook of Applied Cryptography, Table 4.4)
+#[cfg(any(feature = "getrandom", all(feature = "std", unix)))]
+fn miller_rabin_rounds(bits: usize) -> usize {
+    match bits {
+        1300.. => 2,
+        850.. => 3,
+        650.. => 4,
+        550.. => 5,
+        450.. => 6,
+        400.. => 7,
+        350.. => 8,
+        300.. => 9,
+        250.. => 12,
+        200.. => 15,
+        150.. => 18,
+        100.. => 27,
+        _ => 40,
+    }
+}
+
+/// Unsigned integer stored in a fixed array of `NUM_BYTES` little endian bytes, the largest
+/// representable value is `2^(8 * NUM_BYTES) - 1`. Results that don't fit panic, e.g. the
+/// product of a `p` byte and a `q` byte value needs `p + q <= NUM_BYTES`.
 #[derive(Debug, Clone)]
 pub struct BignumFast<const NUM_BYTES: usize> {
     digits: [u8; NUM_BYTES],
     pos: usize,
 }
 
-fn calc_pos(length: usize) -> usize {
-    if length <= 2 {
-        0
-    } else if length % 2 == 0 {
-        length / 2 - 1
-    } else {
-        length / 2
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseError {
+    Empty,
+    InvalidDigit,
+    TooLarge,
+}
+
+impl core::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "cannot parse bignum from empty string"),
+            ParseError::InvalidDigit => write!(f, "invalid digit found in string"),
+            ParseError::TooLarge => write!(f, "number too large to fit in target type"),
+        }
     }
 }
 
+impl core::error::Error for ParseError {}
+
+/// Reduction algorithm used in the inner loop of `BignumFast::pow_mod_with`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReductionStrategy {
+    /// Bitwise long division (same as `pow_mod`)
+    LongDivision,
+    /// Barrett reduction (https://en.wikipedia.org/wiki/Barrett_reduction)
+    Barrett,
+    /// Montgomery multiplication (https://en.wikipedia.org/wiki/Montgomery_modular_multiplication),
+    /// only works for odd moduli
+    Montgomery,
+}
+
+/// Index of the most significant non-zero byte in `digits` (0 for a value of zero)
+fn calc_pos(digits: &[u8]) -> usize {
+    digits.iter().rposition(|e| *e != 0).unwrap_or(0)
+}
+
 impl<const NUM_BYTES: usize> BignumFast<NUM_BYTES> {
     pub fn new() -> Self {
         BignumFast::zero()
@@ -26,6 +85,19 @@ impl<const NUM_BYTES: usize> BignumFast<NUM_BYTES> {
         }
     }
 
+    /// Largest representable value `2^capacity_bits() - 1`, all bytes `0xff`
+    pub fn max_value() -> Self {
+        Self {
+            digits: [0xff; NUM_BYTES],
+            pos: NUM_BYTES - 1,
+        }
+    }
+
+    /// Number of bits available, `NUM_BYTES * 8`
+    pub const fn capacity_bits() -> usize {
+        NUM_BYTES * 8
+    }
+
     pub fn is_zero(&self) -> bool {
         self.pos == 0 && self.digits[0] == 0
     }
@@ -34,8 +106,45 @@ impl<const NUM_BYTES: usize> BignumFast<NUM_BYTES> {
         self.is_zero()
     }
 
+    pub fn is_one(&self) -> bool {
+        self.pos == 0 && self.digits[0] == 1
+    }
+
+    /// Reset to zero in place, only the occupied bytes have to be cleared
+    pub fn set_zero(&mut self) {
+        let len = self.len();
+        self.digits[0..len].fill(0);
+        self.pos = 0;
+    }
+
+    /// Swap `a` and `b` if `condition` is true, without branching on `condition`
+    ///
+    /// All `NUM_BYTES` bytes are processed with a mask derived from the condition, so the
+    /// timing doesn't depend on the condition or the values
+    pub fn conditional_swap(a: &mut Self, b: &mut Self, condition: bool) {
+        let c = core::hint::black_box(condition as u8);
+        let mask = 0u8.wrapping_sub(c);
+        for (x, y) in a.digits.iter_mut().zip(b.digits.iter_mut()) {
+            let t = (*x ^ *y) & mask;
+            *x ^= t;
+            *y ^= t;
+        }
+
+        let t = (a.pos ^ b.pos) & 0usize.wrapping_sub(c as usize);
+        a.pos ^= t;
+        b.pos ^= t;
+    }
+
+    /// `b` if `condition` is true, `a` otherwise, without branching on `condition`
+    pub fn conditional_select(a: &Self, b: &Self, condition: bool) -> Self {
+        let mut result = a.clone();
+        let mut other = b.clone();
+        Self::conditional_swap(&mut result, &mut other, condition);
+        result
+    }
+
     pub fn is_even(&self) -> bool {
-        self.digits[0] % 2 == 0
+        self.digits[0].is_multiple_of(2)
     }
 
     pub fn len(&self) -> usize {
@@ -49,22 +158,25 @@ impl<const NUM_BYTES: usize> BignumFast<NUM_BYTES> {
 
         let mut bignum = Self::new();
 
-        let mut pos_last_non_zero = 0;
         for (i, e) in value.iter().rev().enumerate() {
-            if *e != 0 {
-                pos_last_non_zero = i;
-            }
             bignum.digits[i] = *e;
         }
-
-        bignum.pos = calc_pos(value.len() * 2);
-        if bignum.pos > 0 {
-            bignum.pos = pos_last_non_zero;
-        }
+        bignum.pos = calc_pos(&bignum.digits[0..value.len()]);
 
         Some(bignum)
     }
 
+    /// Same as `from_big_endian`, but the error tells how many bytes were given
+    pub fn try_from_big_endian(value: &[u8]) -> Result<Self, String> {
+        Self::from_big_endian(value).ok_or_else(|| {
+            format!(
+                "{} bytes do not fit into a bignum of {} bytes",
+                value.len(),
+                NUM_BYTES
+            )
+        })
+    }
+
     pub fn from_little_endian(value: &[u8]) -> Option<Self> {
         if value.len() > NUM_BYTES {
             return None;
@@ -72,46 +184,79 @@ impl<const NUM_BYTES: usize> BignumFast<NUM_BYTES> {
 
         let mut bignum = Self::new();
 
-        let mut pos_last_non_zero = 0;
-        for (i, e) in value.iter().enumerate() {
-            if *e != 0 {
-                pos_last_non_zero = i;
-            }
-            bignum.digits[i] = *e;
-        }
+        bignum.digits[0..value.len()].copy_from_slice(value);
+        bignum.pos = calc_pos(&bignum.digits[0..value.len()]);
+
+        Some(bignum)
+    }
+
+    /// Build a bignum from little endian 64 bit limbs,
+    /// returns `None` if the value does not fit into `NUM_BYTES`
+    pub fn from_limbs(limbs: &[u64]) -> Option<Self> {
+        let mut bignum = Self::new();
 
-        bignum.pos = calc_pos(value.len() * 2);
-        if bignum.pos > 0 {
-            bignum.pos = pos_last_non_zero;
+        for (i, limb) in limbs.iter().enumerate() {
+            for (j, b) in limb.to_le_bytes().into_iter().enumerate() {
+                match bignum.digits.get_mut(i * 8 + j) {
+                    Some(digit) => *digit = b,
+                    None if b != 0 => return None,
+                    None => (),
+                }
+            }
         }
+        bignum.pos = calc_pos(&bignum.digits);
 
         Some(bignum)
     }
 
-    pub fn try_from_hex_string(s: &str) -> Result<Self, std::num::ParseIntError> {
-        let s = s.trim_start_matches("0x");
-        let s = s.trim_start_matches('0');
-
-        let mut bignum = Self::new();
-        let len = s.len();
+    /// Minimal big endian representation (a single `0` byte for zero)
+    pub fn to_big_endian_bytes(&self) -> Vec<u8> {
+        self.digits[0..self.len()].iter().rev().copied().collect()
+    }
 
-        bignum.pos = calc_pos(len);
+    /// Minimal little endian representation (a single `0` byte for zero)
+    pub fn to_little_endian_bytes(&self) -> Vec<u8> {
+        self.digits[0..self.len()].to_vec()
+    }
 
-        for i in 0..len / 2 {
-            let b = &s[len - (2 * i + 2)..len - 2 * i];
-            let b = u8::from_str_radix(b, 16)?;
-            bignum.digits[i] = b;
+    /// Big endian representation left-padded with zeros to exactly `len` bytes
+    /// returns `None` if the value does not fit into `len` bytes
+    pub fn to_fixed_big_endian(&self, len: usize) -> Option<Vec<u8>> {
+        let bytes = self.to_big_endian_bytes();
+        if self.is_zero() {
+            return Some(vec![0; len]);
+        }
+        if bytes.len() > len {
+            return None;
         }
 
-        if len % 2 != 0 {
-            let b = &s[0..1];
-            let b = u8::from_str_radix(b, 16)?;
-            bignum.digits[len / 2] = b;
+        let mut res = vec![0; len - bytes.len()];
+        res.extend(bytes);
+        Some(res)
+    }
+
+    /// Parse a hex string (optionally prefixed with `0x`), returns `ParseError::TooLarge`
+    /// instead of panicking if the value does not fit into `NUM_BYTES`
+    pub fn try_from_hex_string(s: &str) -> Result<Self, ParseError> {
+        let bytes = utils::hex_to_le_bytes(s)?;
+        let len = bytes.iter().rposition(|b| *b != 0).map_or(0, |i| i + 1);
+        if len > NUM_BYTES {
+            return Err(ParseError::TooLarge);
         }
 
+        let mut bignum = Self::new();
+        bignum.digits[0..len].copy_from_slice(&bytes[0..len]);
+        bignum.pos = calc_pos(&bignum.digits[0..len]);
+
         Ok(bignum)
     }
 
+    /// Parse a hex string (optionally prefixed with `0x`) from an untrusted source.
+    /// Never panics: the input is validated completely before any digit is written.
+    pub fn parse_untrusted(s: &str) -> Result<Self, ParseError> {
+        Self::try_from_hex_string(s)
+    }
+
     pub fn to_hex_string(&self) -> String {
         if self.pos == 0 && self.digits[0] == 0 {
             return String::from("0x0");
@@ -168,12 +313,7 @@ impl<const NUM_BYTES: usize> BignumFast<NUM_BYTES> {
 
         self.digits[byte_pos] &= !(1 << (pos % 8));
 
-        for (i, e) in self.digits[0..self.len()].iter().enumerate().rev() {
-            if *e != 0 || i == 0 {
-                self.pos = i;
-                return;
-            }
-        }
+        self.pos = calc_pos(&self.digits[0..self.len()]);
     }
 
     pub fn toggle_bit(&mut self, pos: usize) {
@@ -191,16 +331,17 @@ impl<const NUM_BYTES: usize> BignumFast<NUM_BYTES> {
             }
         } else {
             // unset bit
-            for (i, e) in self.digits[0..self.len()].iter().enumerate().rev() {
-                if *e != 0 || i == 0 {
-                    self.pos = i;
-                    return;
-                }
-            }
+            self.pos = calc_pos(&self.digits[0..self.len()]);
         }
     }
 
     pub fn add_ref(&self, rhs: &Self) -> Self {
+        self.checked_add(rhs)
+            .expect("Attempted addition with overflow")
+    }
+
+    /// Addition returning `None` on overflow past `NUM_BYTES`
+    pub fn checked_add(&self, rhs: &Self) -> Option<Self> {
         let (long, short) = match self.pos > rhs.pos {
             true => (self, rhs),
             false => (rhs, self),
@@ -221,23 +362,23 @@ impl<const NUM_BYTES: usize> BignumFast<NUM_BYTES> {
 
         if carry != 0 {
             if bignum.len() == NUM_BYTES {
-                panic!("Attempted addition with overflow");
+                return None;
             }
             bignum.digits[bignum.len()] = carry as u8;
             bignum.pos += 1;
         }
 
-        bignum
+        Some(bignum)
     }
 
     pub fn sub_ref(&self, rhs: &Self) -> Self {
         match self.partial_cmp(rhs) {
-            Some(std::cmp::Ordering::Less) => panic!(
+            Some(core::cmp::Ordering::Less) => panic!(
                 "Result of subtraction would be negative.\nlhs: {}\nrhs: {}",
                 self.to_hex_string(),
                 rhs.to_hex_string()
             ),
-            Some(std::cmp::Ordering::Equal) => return BignumFast::zero(),
+            Some(core::cmp::Ordering::Equal) => return BignumFast::zero(),
             _ => (),
         }
 
@@ -266,42 +407,181 @@ impl<const NUM_BYTES: usize> BignumFast<NUM_BYTES> {
         bignum
     }
 
+    /// Subtraction returning `None` if the result would be negative
+    pub fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+        match self >= rhs {
+            true => Some(self.sub_ref(rhs)),
+            false => None,
+        }
+    }
+
+    /// `self += rhs` without constructing a new bignum
+    pub fn add_assign_ref(&mut self, rhs: &Self) {
+        let len = self.len().max(rhs.len());
+
+        let mut carry = 0;
+        for i in 0..len {
+            let tmp = self.digits[i] as u16 + rhs.digits[i] as u16 + carry;
+            carry = tmp >> 8;
+            self.digits[i] = tmp as u8;
+        }
+        self.pos = self.pos.max(rhs.pos);
+
+        if carry != 0 {
+            if len == NUM_BYTES {
+                panic!("Attempted addition with overflow");
+            }
+            self.digits[len] = carry as u8;
+            self.pos = len;
+        }
+    }
+
+    /// `self -= rhs` without constructing a new bignum
+    pub fn sub_assign_ref(&mut self, rhs: &Self) {
+        if *self < *rhs {
+            panic!(
+                "Result of subtraction would be negative.\nlhs: {}\nrhs: {}",
+                self.to_hex_string(),
+                rhs.to_hex_string()
+            );
+        }
+
+        let mut borrow = 0;
+        for i in 0..self.len() {
+            let (mut diff, mut tmp_borrow) = self.digits[i].overflowing_sub(borrow);
+            borrow = tmp_borrow as u8;
+
+            if i < rhs.len() {
+                (diff, tmp_borrow) = diff.overflowing_sub(rhs.digits[i]);
+                borrow += tmp_borrow as u8;
+            }
+
+            self.digits[i] = diff;
+        }
+        self.pos = calc_pos(&self.digits[0..self.len()]);
+    }
+
+    /// `self *= rhs`, the product is accumulated in a scratch value on the stack
+    pub fn mul_assign_ref(&mut self, rhs: &Self) {
+        *self = self.mul_ref(rhs);
+    }
+
+    /// Add one in place, rippling the carry only as far as needed
+    pub fn inc(&mut self) {
+        for i in 0..NUM_BYTES {
+            let (sum, carry) = self.digits[i].overflowing_add(1);
+            self.digits[i] = sum;
+
+            if !carry {
+                if i > self.pos {
+                    self.pos = i;
+                }
+                return;
+            }
+        }
+
+        panic!("Attempted addition with overflow");
+    }
+
+    /// Subtract one in place, rippling the borrow only as far as needed
+    pub fn dec(&mut self) {
+        if self.is_zero() {
+            panic!("Result of subtraction would be negative.\nlhs: 0x0\nrhs: 0x1");
+        }
+
+        for i in 0..self.len() {
+            let (diff, borrow) = self.digits[i].overflowing_sub(1);
+            self.digits[i] = diff;
+
+            if !borrow {
+                break;
+            }
+        }
+
+        if self.digits[self.pos] == 0 && self.pos > 0 {
+            self.pos -= 1;
+        }
+    }
+
+    /// Returns `self + 1` without constructing a second operand
+    pub fn increment_clone(&self) -> Self {
+        let mut bignum = self.clone();
+        bignum.inc();
+        bignum
+    }
+
+    /// Returns `self - 1` without constructing a second operand
+    pub fn decrement_clone(&self) -> Self {
+        let mut bignum = self.clone();
+        bignum.dec();
+        bignum
+    }
+
     pub fn mul_ref(&self, other: &Self) -> Self {
+        self.checked_mul(other)
+            .expect("Attempted multiplication with overflow")
+    }
+
+    /// Multiplication returning `None` on overflow past `NUM_BYTES`
+    pub fn checked_mul(&self, other: &Self) -> Option<Self> {
         let p = self.len();
         let q = other.len();
-        let base = 256;
 
-        if p + q > NUM_BYTES {
-            panic!("Attempted multiplication with overflow");
+        if p + q <= NUM_BYTES {
+            let mut bignum = BignumFast::new();
+            Self::mul_digits(&self.digits[0..p], &other.digits[0..q], &mut bignum.digits);
+            bignum.pos = calc_pos(&bignum.digits[0..p + q]);
+            return Some(bignum);
+        }
+
+        // the product has p + q - 1 or p + q bytes, so it may still fit
+        if p + q - 1 > NUM_BYTES {
+            return None;
+        }
+        let mut product = vec![0; p + q];
+        Self::mul_digits(&self.digits[0..p], &other.digits[0..q], &mut product);
+        if product[p + q - 1] != 0 {
+            return None;
         }
 
         let mut bignum = BignumFast::new();
+        bignum.digits.copy_from_slice(&product[0..NUM_BYTES]);
+        bignum.pos = calc_pos(&bignum.digits);
+        Some(bignum)
+    }
+
+    /// Schoolbook multiplication of little endian digits into `product` (`a.len() + b.len()` bytes)
+    fn mul_digits(a: &[u8], b: &[u8], product: &mut [u8]) {
+        let (p, q) = (a.len(), b.len());
+        let base = 256;
 
-        let mut pos_last_non_zero = 0;
         for b_i in 0..q {
             let mut carry = 0;
             for a_i in 0..p {
-                let mut tmp = bignum.digits[a_i + b_i] as u16;
-                tmp += carry + self.digits[a_i] as u16 * other.digits[b_i] as u16;
+                let mut tmp = product[a_i + b_i] as u16;
+                tmp += carry + a[a_i] as u16 * b[b_i] as u16;
                 carry = tmp / base;
                 tmp %= base;
-                bignum.digits[a_i + b_i] = tmp as u8;
-                if tmp != 0 {
-                    pos_last_non_zero = a_i + b_i;
-                }
-            }
-            if carry != 0 {
-                pos_last_non_zero = b_i + p;
+                product[a_i + b_i] = tmp as u8;
             }
-            bignum.digits[b_i + p] = carry as u8;
+            product[b_i + p] = carry as u8;
         }
-
-        bignum.pos = pos_last_non_zero;
-
-        bignum
     }
 
+    /// Integer division (unsigned) with remainder (https://en.wikipedia.org/wiki/Division_algorithm#Integer_division_(unsigned)_with_remainder)
+    /// returns (quotient, remainder)
+    ///
+    /// # Panics
+    /// if `rhs` is zero
     pub fn div_with_remainder(&self, rhs: &Self) -> (Self, Self) {
+        if rhs.is_zero() {
+            panic!(
+                "Attempted division by zero.\nlhs: {}\nrhs: {}",
+                self.to_hex_string(),
+                rhs.to_hex_string()
+            );
+        }
+
         let mut q = BignumFast::new();
         let mut r = BignumFast::new();
 
@@ -324,665 +604,2740 @@ impl<const NUM_BYTES: usize> BignumFast<NUM_BYTES> {
         (q, r)
     }
 
-    pub fn pow_mod(self, exponent: Self, modulus: &Self) -> Self {
-        let mut base = self;
-        let mut exp = exponent;
+    /// `self mod rhs` without building the quotient, for the many callers that only need
+    /// the remainder
+    pub fn rem_ref(&self, rhs: &Self) -> Self {
+        if rhs.is_zero() {
+            panic!(
+                "Attempted division by zero.\nlhs: {}\nrhs: {}",
+                self.to_hex_string(),
+                rhs.to_hex_string()
+            );
+        }
 
-        let mut t = BignumFast::from(1);
-        while !exp.is_zero() {
-            if !exp.is_even() {
-                (_, t) = BignumFast::mul_ref(&t, &base).div_with_remainder(modulus);
-            }
-            (_, base) = BignumFast::mul_ref(&base, &base).div_with_remainder(modulus);
-            exp = exp >> 1;
+        // already reduced, e.g. reducing operands of add_mod that are smaller than m
+        if self < rhs {
+            return self.clone();
         }
-        let (_, r) = t.div_with_remainder(modulus);
-        r
-    }
-}
 
-impl<const NUM_BYTES: usize> Default for BignumFast<NUM_BYTES> {
-    fn default() -> Self {
-        Self::new()
-    }
-}
+        let mut r = BignumFast::new();
 
-impl<const NUM_BYTES: usize> PartialEq for BignumFast<NUM_BYTES> {
-    fn eq(&self, other: &Self) -> bool {
-        if self.pos != other.pos {
-            return false;
-        }
+        for i in (0..self.len() * 8).rev() {
+            r = r << 1;
+            if self.get_bit(i) {
+                r.set_bit(0);
+            }
 
-        for (s, o) in self.digits[0..self.len()]
-            .iter()
-            .rev()
-            .zip(other.digits[0..self.len()].iter().rev())
-        {
-            if s != o {
-                return false;
+            if r >= *rhs {
+                r = r.sub_ref(rhs);
             }
         }
 
-        true
+        r
     }
-}
 
-impl<const NUM_BYTES: usize> PartialOrd for BignumFast<NUM_BYTES> {
-    fn lt(&self, other: &Self) -> bool {
-        if self.pos != other.pos {
-            return self.pos.lt(&other.pos);
+    /// Remainder of the division by a small modulus `m` in a single pass from the most to the
+    /// least significant byte (Horner's method)
+    pub fn rem_small(&self, m: u64) -> u64 {
+        if m == 0 {
+            panic!("Attempted division by zero.\nlhs: {}", self.to_hex_string());
         }
 
-        for (s, o) in self.digits[0..self.len()]
-            .iter()
-            .rev()
-            .zip(other.digits[0..self.len()].iter().rev())
-        {
-            if s != o {
-                return s.lt(o);
-            }
+        let m = m as u128;
+        let mut r = 0u128;
+        for d in self.digits[0..self.len()].iter().rev() {
+            r = ((r << 8) | *d as u128) % m;
         }
 
-        false
+        r as u64
     }
 
-    fn le(&self, other: &Self) -> bool {
-        if self.pos != other.pos {
-            return self.pos.lt(&other.pos);
-        }
+    /// Left-to-right binary exponentiation without modulus, panics if the result does not
+    /// fit into `NUM_BYTES`
+    pub fn pow(self, exponent: Self) -> Self {
+        let mut res = BignumFast::from(1u32);
 
-        for (s, o) in self.digits[0..self.len()]
-            .iter()
-            .rev()
-            .zip(other.digits[0..self.len()].iter().rev())
-        {
-            if s != o {
-                return s.lt(o);
-            }
+        // every intermediate value divides the result, so only a too large result overflows
+        for bit in exponent.bits_be() {
+            let next = match bit {
+                true => res.checked_mul(&res).and_then(|r| r.checked_mul(&self)),
+                false => res.checked_mul(&res),
+            };
+            res = next.unwrap_or_else(|| {
+                panic!(
+                    "Result of exponentiation does not fit into {} bytes",
+                    NUM_BYTES
+                )
+            });
         }
 
-        true
+        res
     }
 
-    fn gt(&self, other: &Self) -> bool {
-        if self.pos != other.pos {
-            return self.pos.gt(&other.pos);
+    /// Right-to-left binary exponentiation. The bits of `exponent` are cleared as
+    /// they are consumed, and the intermediate values and the unreduced products are wiped
+    /// once they are no longer needed, so no copies of a secret exponent are left behind
+    /// (e.g. during RSA decryption). Scratch values inside `rem_ref` are not wiped.
+    pub fn pow_mod(self, mut exponent: Self, modulus: &Self) -> Self {
+        // every value is congruent to 0 mod 1
+        if modulus.is_one() {
+            exponent.wipe();
+            return BignumFast::zero();
         }
 
-        for (s, o) in self.digits[0..self.len()]
-            .iter()
-            .rev()
-            .zip(other.digits[0..self.len()].iter().rev())
-        {
-            if s != o {
-                return s.gt(o);
+        let mut base = self;
+        let mut t = BignumFast::from(1u32);
+
+        for i in 0..=exponent.pos {
+            for _ in 0..8 {
+                let bit = exponent.digits[i] & 1;
+                exponent.digits[i] >>= 1;
+
+                if bit == 1 {
+                    let mut tmp = t.mul_ref(&base);
+                    t.wipe();
+                    t = tmp.rem_ref(modulus);
+                    tmp.wipe();
+                }
+                let mut tmp = base.mul_ref(&base);
+                base.wipe();
+                base = tmp.rem_ref(modulus);
+                tmp.wipe();
             }
         }
+        exponent.wipe();
+        base.wipe();
 
-        false
+        let r = t.rem_ref(modulus);
+        t.wipe();
+        r
     }
 
-    fn ge(&self, other: &Self) -> bool {
-        if self.pos != other.pos {
-            return self.pos.gt(&other.pos);
-        }
+    /// Overwrite all digits with zero (see `util::zeroize`)
+    fn wipe(&mut self) {
+        zeroize(&mut self.digits);
+        self.pos = 0;
+    }
 
-        for (s, o) in self.digits[0..self.len()]
-            .iter()
-            .rev()
-            .zip(other.digits[0..self.len()].iter().rev())
-        {
-            if s != o {
-                return s.gt(o);
-            }
-        }
+    /// `(self + other) mod m`
+    pub fn add_mod(&self, other: &Self, m: &Self) -> Self {
+        let a = self.rem_ref(m);
+        let b = other.rem_ref(m);
 
-        true
+        // a, b < m, so a + b < 2m and one subtraction suffices
+        let sum = a.add_ref(&b);
+        match sum >= *m {
+            true => sum.sub_ref(m),
+            false => sum,
+        }
     }
 
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        if self.pos != other.pos {
-            return Some(self.pos.cmp(&other.pos));
-        }
+    /// `(self - other) mod m`
+    pub fn sub_mod(&self, other: &Self, m: &Self) -> Self {
+        let a = self.rem_ref(m);
+        let b = other.rem_ref(m);
 
-        for (s, o) in self.digits[0..self.len()]
-            .iter()
-            .rev()
-            .zip(other.digits[0..self.len()].iter().rev())
-        {
-            if s != o {
-                return Some(s.cmp(o));
-            }
+        match a >= b {
+            true => a.sub_ref(&b),
+            false => m.sub_ref(&b).add_ref(&a),
         }
+    }
+
+    /// `(self * other) mod m`
+    ///
+    /// `NUM_BYTES` has to be at least twice the size of `m` in bytes, so that the product of the
+    /// reduced operands fits
+    pub fn mul_mod(&self, other: &Self, m: &Self) -> Self {
+        let a = self.rem_ref(m);
+        let b = other.rem_ref(m);
 
-        Some(std::cmp::Ordering::Equal)
+        a.mul_ref(&b).rem_ref(m)
     }
-}
 
-impl<const NUM_BYTES: usize> std::ops::Shr<usize> for BignumFast<NUM_BYTES> {
-    type Output = Self;
+    /// Modular multiplicative inverse via the extended Euclidean algorithm
+    /// (https://en.wikipedia.org/wiki/Extended_Euclidean_algorithm#Computing_multiplicative_inverses_in_modular_structures)
+    /// returns `None` if `self` and `modulus` are not coprime
+    pub fn mod_inverse(&self, modulus: &Self) -> Option<Self> {
+        if modulus.is_zero() {
+            return None;
+        }
+
+        let one = BignumFast::from(1u32);
+        let a = self.rem_ref(modulus);
+
+        let (mut r0, mut r1) = (modulus.clone(), a);
+        let (mut t0, mut t1) = (BignumFast::zero(), one.clone());
+
+        while !r1.is_zero() {
+            let (q, r) = r0.div_with_remainder(&r1);
+            (r0, r1) = (r1, r);
+
+            // t0 - q * t1 (mod modulus)
+            let qt = q.mul_ref(&t1).rem_ref(modulus);
+            let t = match t0 >= qt {
+                true => t0.sub_ref(&qt),
+                false => t0.add_ref(modulus).sub_ref(&qt),
+            };
+            (t0, t1) = (t1, t);
+        }
+
+        if !r0.is_one() {
+            return None;
+        }
+
+        let t = t0.rem_ref(modulus);
+        Some(t)
+    }
+
+    /// Inverses of all `values` mod `modulus` with a single `mod_inverse` (Montgomery's trick)
+    ///
+    /// The running products `a_0 * ... * a_i` are inverted once and the individual inverses are
+    /// recovered on the way back with two multiplications each. Values without an inverse, zero
+    /// in particular, are returned as zero.
+    pub fn batch_mod_inverse(values: &[Self], modulus: &Self) -> Vec<Self> {
+        if modulus.is_zero() {
+            return vec![Self::zero(); values.len()];
+        }
+
+        let reduced: Vec<Self> = values.iter().map(|v| v.rem_ref(modulus)).collect();
+
+        // prefix[i] is the product of all nonzero values before i
+        let mut prefix = Vec::with_capacity(reduced.len());
+        let mut acc = BignumFast::from(1u32);
+        for a in reduced.iter() {
+            prefix.push(acc.clone());
+            if !a.is_zero() {
+                acc = acc.mul_mod(a, modulus);
+            }
+        }
+
+        // some value shares a factor with the modulus, only those can't be inverted
+        let Some(mut inv) = acc.mod_inverse(modulus) else {
+            return reduced
+                .iter()
+                .map(|a| a.mod_inverse(modulus).unwrap_or_default())
+                .collect();
+        };
+
+        let mut result = vec![Self::zero(); reduced.len()];
+        for (i, a) in reduced.iter().enumerate().rev() {
+            if a.is_zero() {
+                continue;
+            }
+            result[i] = inv.mul_mod(&prefix[i], modulus);
+            inv = inv.mul_mod(a, modulus);
+        }
+
+        result
+    }
+
+    /// Miller-Rabin primality test (https://en.wikipedia.org/wiki/Miller%E2%80%93Rabin_primality_test)
+    /// with `rounds` random bases, preceded by trial division with the small primes.
+    /// A composite number passes with probability at most 4^-rounds.
+    ///
+    /// `NUM_BYTES` has to be at least twice the size of `self` in bytes, so that the squares
+    /// of the Miller-Rabin test fit
+    #[cfg(any(feature = "getrandom", all(feature = "std", unix)))]
+    pub fn is_probable_prime(&self, rounds: usize) -> bool {
+        assert!(
+            2 * self.len() <= NUM_BYTES,
+            "Can't test a {} byte number for primality with {} bytes",
+            self.len(),
+            NUM_BYTES
+        );
+
+        let two = BignumFast::from(2u32);
+
+        if *self < two {
+            return false;
+        }
+        if self.is_even() {
+            return *self == two;
+        }
+
+        for p in small_primes() {
+            if *self == BignumFast::from(*p as u128) {
+                return true;
+            }
+            if self.rem_small(*p) == 0 {
+                return false;
+            }
+        }
+
+        // self - 1 = d * 2^s with d odd
+        let n_minus_one = self.decrement_clone();
+        let mut d = n_minus_one.clone();
+        let mut s = 0;
+        while d.is_even() {
+            d = d >> 1;
+            s += 1;
+        }
+
+        // bases are drawn from [2, self - 2]
+        let range = self.sub_ref(&BignumFast::from(3u32));
+
+        'witness: for _ in 0..rounds {
+            let a = Self::rand_below(&range).add_ref(&two);
+
+            let mut x = a.pow_mod(d.clone(), self);
+            if x.is_one() || x == n_minus_one {
+                continue;
+            }
+
+            for _ in 1..s {
+                x = x.mul_ref(&x).rem_ref(self);
+                if x == n_minus_one {
+                    continue 'witness;
+                }
+            }
+
+            return false;
+        }
+
+        true
+    }
+
+    /// Random number filling all `NUM_BYTES` bytes
+    #[cfg(any(feature = "getrandom", all(feature = "std", unix)))]
+    pub fn rand() -> Self {
+        let mut bignum = Self::new();
+        rng::fill_bytes(&mut bignum.digits);
+        bignum.pos = calc_pos(&bignum.digits);
+
+        bignum
+    }
+
+    /// Random probable prime with exactly `bits` bits. The two most significant bits are
+    /// set, so the product of two such primes has exactly `2 * bits` bits
+    ///
+    /// `NUM_BYTES` has to be at least twice the size of the prime in bytes, so that the squares
+    /// of the Miller-Rabin test fit
+    #[cfg(any(feature = "getrandom", all(feature = "std", unix)))]
+    pub fn random_prime(bits: usize) -> Self {
+        assert!(
+            bits >= 2 && 2 * bits.div_ceil(8) <= NUM_BYTES,
+            "Can't create a {} bit prime with {} bytes",
+            bits,
+            NUM_BYTES
+        );
+
+        let rounds = miller_rabin_rounds(bits);
+        loop {
+            let mut bytes = vec![0; bits.div_ceil(8)];
+            rng::fill_bytes(&mut bytes);
+            if !bits.is_multiple_of(8) {
+                bytes[0] &= (1 << (bits % 8)) - 1;
+            }
+
+            let mut candidate = Self::from_big_endian(&bytes).unwrap();
+            candidate.set_bit(bits - 1);
+            candidate.set_bit(bits - 2);
+            candidate.set_bit(0);
+
+            if candidate.is_probable_prime(rounds) {
+                return candidate;
+            }
+        }
+    }
+
+    /// Random number in `[0, bound)`
+    ///
+    /// Candidates with the bit length of `bound` are drawn until one is below `bound`, which
+    /// takes less than two tries on average and works for bounds that fill all `NUM_BYTES`
+    #[cfg(any(feature = "getrandom", all(feature = "std", unix)))]
+    fn rand_below(bound: &Self) -> Self {
+        assert!(!bound.is_zero(), "Can't draw a random number below zero");
+
+        let top_bits = bound.bit_length() - 8 * bound.pos;
+        let mut buf = vec![0; bound.len()];
+        loop {
+            rng::fill_bytes(&mut buf);
+            buf[bound.pos] &= (0xff_u16 >> (8 - top_bits)) as u8;
+
+            let candidate = Self::from_little_endian(&buf).unwrap();
+            if candidate < *bound {
+                return candidate;
+            }
+        }
+    }
+
+    /// Modular exponentiation like `pow_mod` with a selectable reduction algorithm
+    pub fn pow_mod_with(self, exponent: Self, modulus: &Self, strategy: ReductionStrategy) -> Self {
+        // every value is congruent to 0 mod 1
+        if modulus.is_one() {
+            return BignumFast::zero();
+        }
+
+        match strategy {
+            ReductionStrategy::LongDivision => self.pow_mod(exponent, modulus),
+            ReductionStrategy::Barrett => self.pow_mod_barrett(exponent, modulus),
+            ReductionStrategy::Montgomery => self.pow_mod_montgomery(exponent, modulus),
+        }
+    }
+
+    fn pow_mod_barrett(self, exponent: Self, modulus: &Self) -> Self {
+        // mu = floor(4^k / modulus) with k the bit length of the modulus
+        let k = modulus.bit_length();
+        let mut four_pow_k = BignumFast::zero();
+        four_pow_k.set_bit(2 * k);
+        let (mu, _) = four_pow_k.div_with_remainder(modulus);
+
+        // x < modulus^2 < 4^k
+        let reduce = |x: Self| -> Self {
+            let q = ((x.clone() >> (k - 1)).mul_ref(&mu)) >> (k + 1);
+            let mut r = x.sub_ref(&q.mul_ref(modulus));
+            while r >= *modulus {
+                r = r.sub_ref(modulus);
+            }
+            r
+        };
+
+        let mut base = self.rem_ref(modulus);
+        let mut exp = exponent;
+
+        let mut t = BignumFast::from(1u32).rem_ref(modulus);
+        while !exp.is_zero() {
+            if !exp.is_even() {
+                t = reduce(t.mul_ref(&base));
+            }
+            base = reduce(base.mul_ref(&base));
+            exp = exp >> 1;
+        }
+        t
+    }
+
+    fn pow_mod_montgomery(self, exponent: Self, modulus: &Self) -> Self {
+        if modulus.is_even() {
+            panic!(
+                "Montgomery reduction requires an odd modulus.\nmodulus: {}",
+                modulus.to_hex_string()
+            );
+        }
+
+        // R = 2^k with k the bit length of the modulus
+        let k = modulus.bit_length();
+
+        // REDC(x) = x * R^-1 mod modulus for x < modulus * R, one bit at a time
+        let redc = |mut x: Self| -> Self {
+            for _ in 0..k {
+                if !x.is_even() {
+                    x = x.add_ref(modulus);
+                }
+                x = x >> 1;
+            }
+            if x >= *modulus {
+                x = x.sub_ref(modulus);
+            }
+            x
+        };
+
+        let base = self.rem_ref(modulus);
+        let mut base = (base << k).rem_ref(modulus);
+        let mut exp = exponent;
+
+        let mut t = (BignumFast::from(1u32) << k).rem_ref(modulus);
+        while !exp.is_zero() {
+            if !exp.is_even() {
+                t = redc(t.mul_ref(&base));
+            }
+            base = redc(base.mul_ref(&base));
+            exp = exp >> 1;
+        }
+        redc(t)
+    }
+
+    /// Position of the most significant set bit plus one (0 for zero)
+    pub fn bit_length(&self) -> usize {
+        if self.is_zero() {
+            return 0;
+        }
+        self.pos * 8 + (8 - self.digits[self.pos].leading_zeros() as usize)
+    }
+
+    /// Bits from the least to the most significant one, `bit_length` bits in total
+    pub fn bits(&self) -> impl DoubleEndedIterator<Item = bool> + '_ {
+        (0..self.bit_length()).map(|i| self.get_bit(i))
+    }
+
+    /// Bits from the most to the least significant one, starting at the highest set bit
+    pub fn bits_be(&self) -> impl Iterator<Item = bool> + '_ {
+        self.bits().rev()
+    }
+
+    /// Integer square root (floor) via Newton's method (https://en.wikipedia.org/wiki/Integer_square_root#Algorithm_using_Newton's_method)
+    pub fn isqrt(&self) -> Self {
+        if self.is_zero() {
+            return BignumFast::zero();
+        }
+
+        // initial guess 2^ceil(bits / 2) is always >= sqrt(self)
+        let mut x = BignumFast::from(1u32) << self.bit_length().div_ceil(2);
+
+        loop {
+            let (q, _) = self.div_with_remainder(&x);
+            let y = x.add_ref(&q) >> 1;
+            if y >= x {
+                return x;
+            }
+            x = y;
+        }
+    }
+
+    /// Number of trailing zero bits (`NUM_BYTES * 8` for zero)
+    pub fn trailing_zeros(&self) -> usize {
+        match self.digits[0..self.len()].iter().position(|d| *d != 0) {
+            Some(i) => i * 8 + self.digits[i].trailing_zeros() as usize,
+            None => NUM_BYTES * 8,
+        }
+    }
+
+    pub fn is_power_of_two(&self) -> bool {
+        self.digits[self.pos].is_power_of_two() && self.digits[0..self.pos].iter().all(|d| *d == 0)
+    }
+
+    /// Number of set bits (Hamming weight)
+    pub fn count_ones(&self) -> u32 {
+        self.digits[0..self.len()]
+            .iter()
+            .map(|d| d.count_ones())
+            .sum()
+    }
+
+    /// Binary GCD algorithm (https://en.wikipedia.org/wiki/Binary_GCD_algorithm)
+    pub fn gcd(&self, other: &Self) -> Self {
+        if self.is_zero() {
+            return other.clone();
+        }
+        if other.is_zero() {
+            return self.clone();
+        }
+
+        let mut a = self.clone();
+        let mut b = other.clone();
+
+        // gcd(2^i * a, 2^j * b) = 2^min(i, j) * gcd(a, b)
+        let (i, j) = (a.trailing_zeros(), b.trailing_zeros());
+        let shift = i.min(j);
+        a = a >> i;
+        b = b >> j;
+
+        loop {
+            let j = b.trailing_zeros();
+            b = b >> j;
+
+            if a > b {
+                core::mem::swap(&mut a, &mut b);
+            }
+
+            b = b.sub_ref(&a);
+            if b.is_zero() {
+                break;
+            }
+        }
+
+        a << shift
+    }
+
+    /// Least common multiple computed as `self / gcd(self, other) * other`
+    pub fn lcm(&self, other: &Self) -> Self {
+        if self.is_zero() || other.is_zero() {
+            return BignumFast::zero();
+        }
+
+        let (q, _) = self.div_with_remainder(&self.gcd(other));
+        q.mul_ref(other)
+    }
+}
+
+impl<const NUM_BYTES: usize> Default for BignumFast<NUM_BYTES> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const NUM_BYTES: usize> PartialEq for BignumFast<NUM_BYTES> {
+    fn eq(&self, other: &Self) -> bool {
+        if self.pos != other.pos {
+            return false;
+        }
+
+        for (s, o) in self.digits[0..self.len()]
+            .iter()
+            .rev()
+            .zip(other.digits[0..self.len()].iter().rev())
+        {
+            if s != o {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+impl<const NUM_BYTES: usize> PartialOrd for BignumFast<NUM_BYTES> {
+    fn lt(&self, other: &Self) -> bool {
+        if self.pos != other.pos {
+            return self.pos.lt(&other.pos);
+        }
+
+        for (s, o) in self.digits[0..self.len()]
+            .iter()
+            .rev()
+            .zip(other.digits[0..self.len()].iter().rev())
+        {
+            if s != o {
+                return s.lt(o);
+            }
+        }
+
+        false
+    }
+
+    fn le(&self, other: &Self) -> bool {
+        if self.pos != other.pos {
+            return self.pos.lt(&other.pos);
+        }
+
+        for (s, o) in self.digits[0..self.len()]
+            .iter()
+            .rev()
+            .zip(other.digits[0..self.len()].iter().rev())
+        {
+            if s != o {
+                return s.lt(o);
+            }
+        }
+
+        true
+    }
+
+    fn gt(&self, other: &Self) -> bool {
+        if self.pos != other.pos {
+            return self.pos.gt(&other.pos);
+        }
+
+        for (s, o) in self.digits[0..self.len()]
+            .iter()
+            .rev()
+            .zip(other.digits[0..self.len()].iter().rev())
+        {
+            if s != o {
+                return s.gt(o);
+            }
+        }
+
+        false
+    }
+
+    fn ge(&self, other: &Self) -> bool {
+        if self.pos != other.pos {
+            return self.pos.gt(&other.pos);
+        }
+
+        for (s, o) in self.digits[0..self.len()]
+            .iter()
+            .rev()
+            .zip(other.digits[0..self.len()].iter().rev())
+        {
+            if s != o {
+                return s.gt(o);
+            }
+        }
+
+        true
+    }
+
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const NUM_BYTES: usize> Eq for BignumFast<NUM_BYTES> {}
+
+/// Hashes only the occupied bytes, consistent with `PartialEq`
+impl<const NUM_BYTES: usize> core::hash::Hash for BignumFast<NUM_BYTES> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.digits[0..self.len()].hash(state);
+    }
+}
+
+impl<const NUM_BYTES: usize> Ord for BignumFast<NUM_BYTES> {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        if self.pos != other.pos {
+            return self.pos.cmp(&other.pos);
+        }
+
+        for (s, o) in self.digits[0..self.len()]
+            .iter()
+            .rev()
+            .zip(other.digits[0..self.len()].iter().rev())
+        {
+            if s != o {
+                return s.cmp(o);
+            }
+        }
+
+        core::cmp::Ordering::Equal
+    }
+}
+
+impl<const NUM_BYTES: usize> core::ops::Shr<usize> for BignumFast<NUM_BYTES> {
+    type Output = Self;
 
     fn shr(mut self, rhs: usize) -> Self::Output {
         let shift = (rhs % 8) as u8;
         let bytes_shift = rhs / 8;
 
-        if bytes_shift >= self.len() {
-            return Self::zero();
-        }
+        if bytes_shift >= self.len() {
+            return Self::zero();
+        }
+
+        // move the occupied bytes down and clear the vacated ones, reading past `pos` would run
+        // out of bounds if the value occupies the highest byte
+        let len = self.len();
+        self.digits.copy_within(bytes_shift..len, 0);
+        self.digits[len - bytes_shift..len].fill(0);
+
+        if shift == 0 {
+            self.pos -= bytes_shift;
+            return self;
+        }
+
+        let mut carry = 0;
+        for i in (0..self.len()).rev() {
+            let tmp_carry = (self.digits[i] as u16) << (8 - shift);
+            self.digits[i] >>= shift;
+            self.digits[i] |= carry;
+            carry = tmp_carry as u8;
+        }
+
+        self.pos -= bytes_shift;
+        if self.digits[self.pos] == 0 && self.pos > 0 {
+            self.pos -= 1;
+        }
+
+        self
+    }
+}
+
+impl<const NUM_BYTES: usize> core::ops::Shl<usize> for BignumFast<NUM_BYTES> {
+    type Output = Self;
+
+    fn shl(mut self, rhs: usize) -> Self::Output {
+        let shift = (rhs % 8) as u8;
+        let mut bytes_shift = rhs / 8;
+
+        if bytes_shift + self.len() > NUM_BYTES {
+            bytes_shift = 0;
+        }
+
+        if bytes_shift > 0 {
+            for i in (bytes_shift..self.len() + bytes_shift).rev() {
+                self.digits[i] = self.digits[i - bytes_shift];
+            }
+
+            for i in 0..bytes_shift {
+                self.digits[i] = 0;
+            }
+        }
+
+        let mut carry = 0;
+        for i in bytes_shift..self.len() + bytes_shift {
+            let tmp_carry = (self.digits[i] as u16) >> (8 - shift);
+            self.digits[i] <<= shift;
+            self.digits[i] |= carry;
+            carry = tmp_carry as u8;
+        }
+
+        self.pos += bytes_shift;
+        if carry != 0 && self.len() < NUM_BYTES {
+            self.digits[self.len()] = carry;
+            self.pos += 1;
+        }
+
+        self
+    }
+}
+
+impl<const NUM_BYTES: usize> core::ops::Add for BignumFast<NUM_BYTES> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.add_ref(&rhs)
+    }
+}
+
+impl<const NUM_BYTES: usize> core::ops::Sub for BignumFast<NUM_BYTES> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.sub_ref(&rhs)
+    }
+}
+
+impl<const NUM_BYTES: usize> core::ops::Mul for BignumFast<NUM_BYTES> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.mul_ref(&rhs)
+    }
+}
+
+impl<const NUM_BYTES: usize> core::ops::Div for BignumFast<NUM_BYTES> {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        let (q, _) = self.div_with_remainder(&rhs);
+        q
+    }
+}
+
+impl<const NUM_BYTES: usize> core::ops::Rem for BignumFast<NUM_BYTES> {
+    type Output = Self;
+
+    fn rem(self, rhs: Self) -> Self::Output {
+        self.rem_ref(&rhs)
+    }
+}
+
+impl<const NUM_BYTES: usize> core::ops::AddAssign for BignumFast<NUM_BYTES> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.add_assign_ref(&rhs);
+    }
+}
+
+impl<const NUM_BYTES: usize> core::ops::SubAssign for BignumFast<NUM_BYTES> {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.sub_assign_ref(&rhs);
+    }
+}
+
+impl<const NUM_BYTES: usize> core::ops::MulAssign for BignumFast<NUM_BYTES> {
+    fn mul_assign(&mut self, rhs: Self) {
+        self.mul_assign_ref(&rhs);
+    }
+}
+
+impl<const NUM_BYTES: usize> core::ops::DivAssign for BignumFast<NUM_BYTES> {
+    fn div_assign(&mut self, rhs: Self) {
+        (*self, _) = self.div_with_remainder(&rhs);
+    }
+}
+
+impl<const NUM_BYTES: usize> core::ops::RemAssign for BignumFast<NUM_BYTES> {
+    fn rem_assign(&mut self, rhs: Self) {
+        *self = self.rem_ref(&rhs);
+    }
+}
+
+macro_rules! impl_from_uint {
+    ($($t:ty),*) => {
+        $(
+            impl<const NUM_BYTES: usize> From<$t> for BignumFast<NUM_BYTES> {
+                /// # Panics
+                /// if `value` does not fit into `NUM_BYTES`
+                fn from(value: $t) -> Self {
+                    let bytes = value.to_le_bytes();
+                    let len = calc_pos(&bytes) + 1;
+                    if len > NUM_BYTES {
+                        panic!("{} does not fit into {} bytes", value, NUM_BYTES);
+                    }
+
+                    let mut bignum = BignumFast::new();
+                    bignum.digits[0..len].copy_from_slice(&bytes[0..len]);
+                    bignum.pos = len - 1;
+
+                    bignum
+                }
+            }
+        )*
+    };
+}
+impl_from_uint!(u32, u64, u128);
+
+/// Big endian, fails if the slice is longer than `NUM_BYTES`
+impl<const NUM_BYTES: usize> TryFrom<&[u8]> for BignumFast<NUM_BYTES> {
+    type Error = ParseError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        BignumFast::from_big_endian(value).ok_or(ParseError::TooLarge)
+    }
+}
+
+/// Parses hex with a `0x` prefix (see `parse_untrusted`) and decimal otherwise
+impl<const NUM_BYTES: usize> core::str::FromStr for BignumFast<NUM_BYTES> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.starts_with("0x") {
+            return BignumFast::parse_untrusted(s);
+        }
+
+        if s.is_empty() {
+            return Err(ParseError::Empty);
+        }
+        if !s.bytes().all(|c| c.is_ascii_digit()) {
+            return Err(ParseError::InvalidDigit);
+        }
+
+        let ten = BignumFast::from(10u32);
+        let mut bignum = BignumFast::zero();
+        for c in s.bytes() {
+            bignum = bignum
+                .checked_mul(&ten)
+                .and_then(|b| b.checked_add(&BignumFast::from((c - b'0') as u32)))
+                .ok_or(ParseError::TooLarge)?;
+        }
+
+        Ok(bignum)
+    }
+}
+
+/// Serialized as a hex string (`"0xabc"`) for human readable formats and as
+/// minimal big endian bytes otherwise
+#[cfg(feature = "serde")]
+impl<const NUM_BYTES: usize> serde::Serialize for BignumFast<NUM_BYTES> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match serializer.is_human_readable() {
+            true => serializer.serialize_str(&self.to_hex_string()),
+            false => serializer.serialize_bytes(&self.to_big_endian_bytes()),
+        }
+    }
+}
+
+/// Accepts a hex string or big endian bytes, rejecting values that don't fit into `NUM_BYTES`
+#[cfg(feature = "serde")]
+impl<'de, const NUM_BYTES: usize> serde::Deserialize<'de> for BignumFast<NUM_BYTES> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor<const NUM_BYTES: usize>;
+
+        impl<'de, const NUM_BYTES: usize> serde::de::Visitor<'de> for Visitor<NUM_BYTES> {
+            type Value = BignumFast<NUM_BYTES>;
+
+            fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                write!(f, "a hex string or at most {} big endian bytes", NUM_BYTES)
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                BignumFast::parse_untrusted(v).map_err(E::custom)
+            }
+
+            fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+                BignumFast::from_big_endian(v).ok_or_else(|| E::invalid_length(v.len(), &self))
+            }
+
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> Result<Self::Value, A::Error> {
+                let mut bytes = [0; NUM_BYTES];
+                let mut len = 0;
+                while let Some(b) = seq.next_element()? {
+                    if len == NUM_BYTES {
+                        return Err(serde::de::Error::invalid_length(len + 1, &self));
+                    }
+                    bytes[len] = b;
+                    len += 1;
+                }
+                self.visit_bytes(&bytes[..len])
+            }
+        }
+
+        match deserializer.is_human_readable() {
+            true => deserializer.deserialize_str(Visitor),
+            false => deserializer.deserialize_bytes(Visitor),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::unsigned_bignum::UnsignedBignum;
+
+    const N: usize = 200;
+
+    fn check_pos<const NUM_BYTES: usize>(bn: &BignumFast<NUM_BYTES>) {
+        let mut pos_last_non_zero = 0;
+        for (i, e) in bn.digits.iter().enumerate() {
+            if *e != 0 {
+                pos_last_non_zero = i;
+            }
+        }
+
+        assert_eq!(pos_last_non_zero, bn.pos);
+    }
+
+    fn get_arithmatik_test_cases() -> Vec<(u128, u128)> {
+        let mut test_cases: Vec<(u128, u128)> = vec![(0, 0xa), (0xa, 0), (0, 0)];
+        for a in (0..0xabcedef).step_by(5_000_000) {
+            for b in (0..0xabcedef).step_by(5_000_000) {
+                test_cases.push((a, b));
+            }
+        }
+
+        test_cases
+    }
+
+    fn get_bit_manipulation_test_cases() -> Vec<(u128, usize)> {
+        let base = 0xabcedef;
+        let mut test_cases: Vec<(u128, usize)> = vec![];
+        for i in 0..127 {
+            test_cases.push((base, i));
+        }
+
+        test_cases
+    }
+
+    #[test]
+    fn hex_string() {
+        for (s, p) in [
+            ("0x11abcdef", 3),
+            ("0x1abcdef", 3),
+            ("0xabcdef", 2),
+            ("0x1cdef", 2),
+            ("0xcdef", 1),
+            ("0xdef", 1),
+            ("0xef", 0),
+            ("0xf", 0),
+            ("0x0", 0),
+            ("0x1", 0),
+        ] {
+            let bignum: BignumFast<N> = BignumFast::try_from_hex_string(s).unwrap();
+            check_pos(&bignum);
+
+            assert_eq!(p, bignum.pos);
+            assert_eq!(s, bignum.to_hex_string());
+        }
+    }
+
+    #[test]
+    fn hex_string_2() {
+        for (a, b) in get_arithmatik_test_cases() {
+            let s = format!("{:#02x}", a);
+
+            let bignum: BignumFast<N> = BignumFast::try_from_hex_string(&s).unwrap();
+            check_pos(&bignum);
+
+            let bignum2: BignumFast<N> = BignumFast::from(a);
+            assert_eq!(bignum, bignum2);
+
+            let s = format!("{:#02x}", b);
+
+            let bignum: BignumFast<N> = BignumFast::try_from_hex_string(&s).unwrap();
+            check_pos(&bignum);
+
+            let bignum2: BignumFast<N> = BignumFast::from(b);
+            assert_eq!(bignum, bignum2);
+        }
+    }
+
+    #[test]
+    fn hex_string_interior_zeros() {
+        for a in [
+            0x1_0000_0000u128,
+            0x1_0000_0000_0000_0000,
+            0x100_0000_0000_0000_0000_0000_0001,
+            0x1000_0000_0100_0000_0010_0000_0000,
+            u128::MAX,
+        ] {
+            let bignum: BignumFast<N> = BignumFast::from(a);
+            assert_eq!(bignum.to_hex_string(), format!("{:#x}", a));
+
+            let bignum2 = BignumFast::try_from_hex_string(&bignum.to_hex_string()).unwrap();
+            check_pos(&bignum2);
+            assert_eq!(bignum, bignum2);
+        }
+
+        for _ in 0..20 {
+            let bignum: BignumFast<N> = BignumFast::rand();
+            let bignum2 = BignumFast::try_from_hex_string(&bignum.to_hex_string()).unwrap();
+            check_pos(&bignum2);
+            assert_eq!(bignum, bignum2);
+        }
+    }
+
+    #[test]
+    fn len() {
+        for (s, l) in [
+            ("0x11abcdef", 4),
+            ("0x1abcdef", 4),
+            ("0xabcdef", 3),
+            ("0x1cdef", 3),
+            ("0xcdef", 2),
+            ("0xdef", 2),
+            ("0xef", 1),
+            ("0xf", 1),
+            ("0x0", 1),
+            ("0x1", 1),
+        ] {
+            let bignum: BignumFast<N> = BignumFast::try_from_hex_string(s).unwrap();
+            check_pos(&bignum);
+
+            println!("{:?} {}", &bignum.digits[0..7], bignum.pos);
+            assert_eq!(l, bignum.len());
+        }
+    }
+
+    #[test]
+    fn pos() {
+        for (s, p) in [
+            ("0x11abcdef", 3),
+            ("0x1abcdef", 3),
+            ("0xabcdef", 2),
+            ("0x1cdef", 2),
+            ("0xcdef", 1),
+            ("0xdef", 1),
+            ("0xef", 0),
+            ("0xf", 0),
+            ("0x0", 0),
+            ("0x1", 0),
+        ] {
+            let bignum: BignumFast<N> = BignumFast::try_from_hex_string(s).unwrap();
+            check_pos(&bignum);
+
+            println!("{} {:?} {}", s, &bignum.digits[0..7], bignum.pos);
+            assert_eq!(p, bignum.pos);
+        }
+    }
+
+    #[test]
+    fn pos_boundaries() {
+        for (s, p) in [
+            ("0x1000000000000000", 7),
+            ("0xffffffffffffffff", 7),
+            ("0x10000000000000000", 8),
+            ("0x1ffffffffffffffff", 8),
+            ("0x10000000000000000000000000000000", 15),
+            ("0xffffffffffffffffffffffffffffffff", 15),
+            ("0x100000000000000000000000000000001", 16),
+            ("0x0000000000000000000000000000000f", 0),
+            ("0x00000000000000010000000000000000", 8),
+        ] {
+            let bignum: BignumFast<N> = BignumFast::try_from_hex_string(s).unwrap();
+            check_pos(&bignum);
+            assert_eq!(p, bignum.pos);
+
+            let mut bytes = bignum.digits[0..bignum.len()].to_vec();
+            bytes.extend([0, 0]);
+            let bignum_le: BignumFast<N> = BignumFast::from_little_endian(&bytes).unwrap();
+            check_pos(&bignum_le);
+            assert_eq!(bignum, bignum_le);
+
+            bytes.reverse();
+            let bignum_be: BignumFast<N> = BignumFast::from_big_endian(&bytes).unwrap();
+            check_pos(&bignum_be);
+            assert_eq!(bignum, bignum_be);
+        }
+    }
+
+    #[test]
+    fn from_little_endian() {
+        for (e, s) in [
+            (vec![0, 0], "0x0"),
+            (vec![1, 0], "0x1"),
+            (vec![0, 1, 0], "0x100"),
+            (vec![0, 1, 2, 0], "0x20100"),
+            (vec![0, 1, 2, 3, 0], "0x3020100"),
+            (vec![0, 1, 2, 3, 4, 0], "0x403020100"),
+        ] {
+            let bignum: BignumFast<N> = BignumFast::from_little_endian(&e).unwrap();
+            check_pos(&bignum);
+
+            assert_eq!(bignum.to_hex_string(), s);
+        }
+    }
+
+    #[test]
+    fn from_big_endian() {
+        for (e, s) in [
+            (vec![0, 0], "0x0"),
+            (vec![1, 0], "0x100"),
+            (vec![0, 1, 0], "0x100"),
+            (vec![0, 1, 2, 0], "0x10200"),
+            (vec![0, 1, 2, 3, 0], "0x1020300"),
+            (vec![0, 1, 2, 3, 4, 0], "0x102030400"),
+        ] {
+            let bignum: BignumFast<N> = BignumFast::from_big_endian(&e).unwrap();
+            check_pos(&bignum);
+
+            assert_eq!(bignum.to_hex_string(), s);
+        }
+    }
+
+    #[test]
+    fn try_from_big_endian() {
+        let bytes: Vec<u8> = (1..=N as u8).collect();
+        let bignum: BignumFast<N> = BignumFast::try_from_big_endian(&bytes).unwrap();
+        check_pos(&bignum);
+        assert_eq!(Some(bignum), BignumFast::from_big_endian(&bytes));
+
+        let too_long = vec![1; N + 1];
+        assert_eq!(
+            BignumFast::<N>::try_from_big_endian(&too_long),
+            Err(format!(
+                "{} bytes do not fit into a bignum of {} bytes",
+                N + 1,
+                N
+            ))
+        );
+        assert_eq!(BignumFast::<N>::from_big_endian(&too_long), None);
+    }
+
+    #[test]
+    fn from_limbs() {
+        for (e, s) in [
+            (vec![], "0x0"),
+            (vec![0, 0], "0x0"),
+            (vec![0xff], "0xff"),
+            (vec![0, 1], "0x10000000000000000"),
+            (
+                vec![0x0706050403020100, 0x0f0e0d0c0b0a0908, 0x10, 0],
+                "0x100f0e0d0c0b0a09080706050403020100",
+            ),
+        ] {
+            let bignum: BignumFast<N> = BignumFast::from_limbs(&e).unwrap();
+            check_pos(&bignum);
+
+            assert_eq!(bignum.to_hex_string(), s);
+        }
+
+        for (a, b) in get_arithmatik_test_cases() {
+            let big_a: BignumFast<N> =
+                BignumFast::from_limbs(&[a as u64, (a >> 64) as u64]).unwrap();
+            assert_eq!(big_a, BignumFast::from(a));
+
+            let big_b: BignumFast<N> = BignumFast::from_limbs(&[b as u64]).unwrap();
+            let product: BignumFast<N> =
+                BignumFast::from_limbs(&[(a * b) as u64, ((a * b) >> 64) as u64]).unwrap();
+            check_pos(&product);
+            assert_eq!(product, big_a * big_b);
+        }
+
+        // N = 200 bytes are exactly 25 limbs
+        let max: BignumFast<N> = BignumFast::from_limbs(&[u64::MAX; N / 8]).unwrap();
+        check_pos(&max);
+        assert_eq!(max, BignumFast::max_value());
+        assert_eq!(BignumFast::<N>::from_limbs(&[1; N / 8 + 1]), None);
+
+        // leading zero limbs past the capacity are fine
+        let mut limbs = vec![0; N / 8 + 2];
+        limbs[0] = 1;
+        assert_eq!(
+            BignumFast::<N>::from_limbs(&limbs),
+            Some(BignumFast::from(1u32))
+        );
+
+        // limbs only partially covered by the capacity have to be zero in the upper bytes
+        assert_eq!(
+            BignumFast::<12>::from_limbs(&[0, u32::MAX as u64]),
+            Some(BignumFast::from((u32::MAX as u128) << 64))
+        );
+        assert_eq!(BignumFast::<12>::from_limbs(&[0, 1 << 32]), None);
+    }
+
+    #[test]
+    fn from_u128() {
+        for (a, b) in get_arithmatik_test_cases() {
+            let bignum: BignumFast<N> = BignumFast::from(a);
+            check_pos(&bignum);
+            let s = format!("{:#02x}", a);
+            assert_eq!(bignum.to_hex_string(), s);
+
+            let bignum: BignumFast<N> = BignumFast::from(b);
+            check_pos(&bignum);
+            let s = format!("{:#02x}", b);
+            assert_eq!(bignum.to_hex_string(), s);
+        }
+    }
+
+    #[test]
+    fn shift_right() {
+        let base = 0xabcedef;
+        let mut test_cases: Vec<(u128, usize)> = vec![];
+        for i in 0..127 {
+            test_cases.push((base, i));
+        }
+
+        for (a, b) in test_cases {
+            let big_a: BignumFast<N> = BignumFast::from(a);
+            check_pos(&big_a);
+
+            let res: BignumFast<N> = BignumFast::from(a >> b);
+            check_pos(&res);
+            let res_big: BignumFast<N> = big_a >> b;
+            check_pos(&res_big);
+
+            assert_eq!(res, res_big);
+        }
+    }
+
+    #[test]
+    fn shift_right_boundaries() {
+        let test_cases: Vec<(u128, usize)> = vec![
+            (0x0100, 8),
+            (0x0100, 9),
+            (0x01ff, 8),
+            (0x0180, 1),
+            (0x0100, 1),
+            (0x0001, 1),
+            (0x00010000, 16),
+            (0x00010000, 15),
+            (0x00010000, 17),
+            (0x0102, 16),
+            (0, 3),
+        ];
+
+        for (a, b) in test_cases {
+            let big_a: BignumFast<N> = BignumFast::from(a);
+            let res_big = big_a >> b;
+            check_pos(&res_big);
+
+            assert_eq!(res_big, BignumFast::from(a >> b));
+        }
+
+        // value occupying the highest byte
+        for b in 0..32 {
+            let big_a: BignumFast<4> = BignumFast::from(0xff80_0001u32);
+            let res_big = big_a >> b;
+            check_pos(&res_big);
+
+            assert_eq!(res_big, BignumFast::from(0xff80_0001u32 >> b));
+        }
+    }
+
+    #[test]
+    fn shift_left() {
+        let base = 0xabcedef;
+        let mut test_cases: Vec<(u128, usize)> = vec![];
+        for i in 0..95 {
+            test_cases.push((base, i));
+        }
+
+        for (a, b) in test_cases {
+            let big_a: BignumFast<N> = BignumFast::from(a);
+            check_pos(&big_a);
+
+            let res: BignumFast<N> = BignumFast::from(a << b);
+            check_pos(&res);
+            let res_big: BignumFast<N> = big_a << b;
+            check_pos(&res_big);
+
+            assert_eq!(res, res_big);
+        }
+    }
+
+    #[test]
+    fn addition() {
+        for (a, b) in get_arithmatik_test_cases() {
+            let big_a: BignumFast<N> = BignumFast::from(a);
+            check_pos(&big_a);
+            let big_b: BignumFast<N> = BignumFast::from(b);
+            check_pos(&big_b);
+
+            let res: BignumFast<N> = BignumFast::from(a + b);
+            check_pos(&res);
+            let res_big: BignumFast<N> = big_a + big_b;
+            check_pos(&res_big);
+
+            assert_eq!(res, res_big);
+        }
+    }
+
+    #[test]
+    fn subtraction() {
+        for (a, b) in get_arithmatik_test_cases() {
+            let (a, b) = match a >= b {
+                true => (a, b),
+                false => (b, a),
+            };
+
+            let big_a: BignumFast<N> = BignumFast::from(a);
+            check_pos(&big_a);
+            let big_b: BignumFast<N> = BignumFast::from(b);
+            check_pos(&big_b);
+
+            let res: BignumFast<N> = BignumFast::from(a - b);
+            check_pos(&res);
+            let res_big: BignumFast<N> = big_a - big_b;
+            check_pos(&res_big);
+
+            assert_eq!(res, res_big);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn subtraction_panic() {
+        for (a, b) in get_arithmatik_test_cases() {
+            let (a, b) = match a >= b {
+                false => (a, b),
+                true => (b, a),
+            };
+
+            let big_a: BignumFast<N> = BignumFast::from(a);
+            check_pos(&big_a);
+            let big_b: BignumFast<N> = BignumFast::from(b);
+            check_pos(&big_b);
+
+            let _res_big: BignumFast<N> = big_a - big_b;
+        }
+    }
+
+    #[test]
+    fn multiplication() {
+        for (a, b) in get_arithmatik_test_cases() {
+            let big_a: BignumFast<N> = BignumFast::from(a);
+            check_pos(&big_a);
+            let big_b: BignumFast<N> = BignumFast::from(b);
+            check_pos(&big_b);
+
+            let res: BignumFast<N> = BignumFast::from(a * b);
+            check_pos(&res);
+            let res_big: BignumFast<N> = big_a * big_b;
+            check_pos(&res_big);
+
+            assert_eq!(res, res_big);
+        }
+    }
+
+    #[test]
+    fn assign_operators() {
+        for (a, b) in get_arithmatik_test_cases() {
+            let big_a: BignumFast<N> = BignumFast::from(a);
+            let big_b: BignumFast<N> = BignumFast::from(b);
+
+            let mut res = big_a.clone();
+            res += big_b.clone();
+            check_pos(&res);
+            assert_eq!(res, big_a.clone() + big_b.clone());
+
+            res -= big_b.clone();
+            check_pos(&res);
+            assert_eq!(res, big_a);
+
+            let mut res = big_a.clone();
+            res *= big_b.clone();
+            check_pos(&res);
+            assert_eq!(res, big_a.clone() * big_b.clone());
+
+            let (big_a, big_b) = match a >= b {
+                true => (big_a, big_b),
+                false => (big_b, big_a),
+            };
+            let mut res = big_a.clone();
+            res.sub_assign_ref(&big_b);
+            check_pos(&res);
+            assert_eq!(res, big_a.clone() - big_b.clone());
+
+            res.add_assign_ref(&big_b);
+            check_pos(&res);
+            assert_eq!(res, big_a);
+        }
+
+        // carry into a new byte and borrow out of the top byte
+        let mut a: BignumFast<N> = BignumFast::from(0xffffu32);
+        a.add_assign_ref(&BignumFast::from(1u32));
+        check_pos(&a);
+        assert_eq!(a, BignumFast::from(0x10000u32));
+        a.sub_assign_ref(&BignumFast::from(1u32));
+        check_pos(&a);
+        assert_eq!(a, BignumFast::from(0xffffu32));
+    }
+
+    #[test]
+    #[should_panic(expected = "overflow")]
+    fn add_assign_overflow() {
+        let mut a = BignumFast::<N>::max_value();
+        a += BignumFast::from(1u32);
+    }
+
+    #[test]
+    #[should_panic(expected = "negative")]
+    fn sub_assign_negative() {
+        let mut a: BignumFast<N> = BignumFast::from(1u32);
+        a -= BignumFast::from(2u32);
+    }
+
+    #[test]
+    fn division_with_remainder() {
+        for (a, b) in get_arithmatik_test_cases() {
+            if b == 0 {
+                continue;
+            }
+            let big_a: BignumFast<N> = BignumFast::from(a);
+            check_pos(&big_a);
+            let big_b: BignumFast<N> = BignumFast::from(b);
+            check_pos(&big_b);
+
+            let (big_q, big_r) = BignumFast::div_with_remainder(&big_a, &big_b);
+            check_pos(&big_q);
+            check_pos(&big_r);
+            let q: BignumFast<N> = BignumFast::from(a / b);
+            let r: BignumFast<N> = BignumFast::from(a % b);
+            check_pos(&q);
+            check_pos(&r);
+
+            assert_eq!(big_q, q);
+            assert_eq!(big_r, r);
+        }
+    }
+
+    #[test]
+    fn rem_ref() {
+        for (a, b) in get_arithmatik_test_cases() {
+            if b == 0 {
+                continue;
+            }
+            let big_a: BignumFast<N> = BignumFast::from(a);
+            let big_b: BignumFast<N> = BignumFast::from(b);
+
+            let big_r = big_a.rem_ref(&big_b);
+            check_pos(&big_r);
+            assert_eq!(big_r, BignumFast::from(a % b));
+        }
+
+        // values wider than 128 bit
+        for _ in 0..20 {
+            let big_a: BignumFast<N> = BignumFast::rand();
+            let big_b: BignumFast<N> = BignumFast::rand() >> 100;
+
+            let (_, r) = big_a.div_with_remainder(&big_b);
+            assert_eq!(big_a.rem_ref(&big_b), r);
+            assert_eq!(big_b.rem_ref(&big_a), big_b);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "division by zero")]
+    fn rem_ref_by_zero() {
+        let a: BignumFast<N> = BignumFast::from(0xabcdefu32);
+        let _ = a.rem_ref(&BignumFast::zero());
+    }
+
+    #[test]
+    fn division_remainder_operators() {
+        for (a, b) in get_arithmatik_test_cases() {
+            if b == 0 {
+                continue;
+            }
+            let big_a: BignumFast<N> = BignumFast::from(a);
+            let big_b: BignumFast<N> = BignumFast::from(b);
+
+            let q: BignumFast<N> = BignumFast::from(a / b);
+            let r: BignumFast<N> = BignumFast::from(a % b);
+
+            let big_q = big_a.clone() / big_b.clone();
+            check_pos(&big_q);
+            assert_eq!(big_q, q);
+
+            let big_r = big_a.clone() % big_b.clone();
+            check_pos(&big_r);
+            assert_eq!(big_r, r);
+
+            let mut big_q = big_a.clone();
+            big_q /= big_b.clone();
+            check_pos(&big_q);
+            assert_eq!(big_q, q);
+
+            let mut big_r = big_a;
+            big_r %= big_b;
+            check_pos(&big_r);
+            assert_eq!(big_r, r);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "division by zero")]
+    fn division_by_zero() {
+        let a: BignumFast<N> = BignumFast::from(0xabcdefu32);
+        let _ = a.div_with_remainder(&BignumFast::zero());
+    }
+
+    #[test]
+    #[should_panic(expected = "division by zero")]
+    fn division_zero_by_zero() {
+        let a: BignumFast<N> = BignumFast::zero();
+        let _ = a / BignumFast::zero();
+    }
+
+    #[test]
+    fn rem_small() {
+        for (a, b) in get_arithmatik_test_cases() {
+            if b == 0 {
+                continue;
+            }
+            let big_a: BignumFast<N> = BignumFast::from(a);
+            let big_b: BignumFast<N> = BignumFast::from(b);
+
+            let (_, big_r) = big_a.div_with_remainder(&big_b);
+            let r: BignumFast<N> = BignumFast::from(big_a.rem_small(b as u64) as u128);
+
+            assert_eq!(big_r, r);
+        }
+
+        let big_a: BignumFast<N> = BignumFast::try_from_hex_string(
+            "0xd00e8de65f7c32094b732a5628cefedc35ed796b7cea6297614545df71d8dbc6",
+        )
+        .unwrap();
+        for m in [
+            1,
+            2,
+            3,
+            0xff,
+            0x10001,
+            u32::MAX as u64,
+            u64::MAX - 1,
+            u64::MAX,
+        ] {
+            let big_m: BignumFast<N> = BignumFast::from(m as u128);
+            let (_, big_r) = big_a.div_with_remainder(&big_m);
+
+            assert_eq!(big_r, BignumFast::from(big_a.rem_small(m) as u128));
+        }
+    }
+
+    #[test]
+    fn comparison() {
+        for (a, b) in get_arithmatik_test_cases() {
+            let big_a: BignumFast<N> = BignumFast::from(a);
+            let big_b: BignumFast<N> = BignumFast::from(b);
+            check_pos(&big_a);
+            check_pos(&big_b);
+
+            let res = a.eq(&b);
+            let res_big = big_a.eq(&big_b);
+            assert_eq!(res, res_big);
+
+            let res = a.lt(&b);
+            let res_big = big_a.lt(&big_b);
+            assert_eq!(res, res_big);
+
+            let res = a.le(&b);
+            let res_big = big_a.le(&big_b);
+            assert_eq!(res, res_big);
+
+            let res = a.gt(&b);
+            let res_big = big_a.gt(&big_b);
+            assert_eq!(res, res_big);
+
+            let res = a.ge(&b);
+            let res_big = big_a.ge(&big_b);
+            assert_eq!(res, res_big);
+
+            let res = a.partial_cmp(&b);
+            let res_big = big_a.partial_cmp(&big_b);
+            assert_eq!(res, res_big);
+        }
+    }
+
+    #[test]
+    fn get_bit() {
+        for (a, b) in get_bit_manipulation_test_cases() {
+            let big_a: BignumFast<N> = BignumFast::from(a);
+            check_pos(&big_a);
+
+            let res = (a >> b) & 1 == 1;
+            let res_big = big_a.get_bit(b);
+
+            assert_eq!(res, res_big);
+        }
+    }
+
+    #[test]
+    fn set_bit() {
+        for (mut a, b) in get_bit_manipulation_test_cases() {
+            let mut big_a: BignumFast<N> = BignumFast::from(a);
+            check_pos(&big_a);
+            big_a.set_bit(b);
+            check_pos(&big_a);
+
+            a |= 1 << b;
+            let a = BignumFast::from(a);
+            check_pos(&a);
+
+            assert_eq!(a, big_a);
+        }
+    }
+
+    #[test]
+    fn unset_bit() {
+        for (mut a, b) in get_bit_manipulation_test_cases() {
+            let mut big_a: BignumFast<N> = BignumFast::from(a);
+            check_pos(&big_a);
+            big_a.unset_bit(b);
+            check_pos(&big_a);
+
+            a &= !(1 << b);
+            let a = BignumFast::from(a);
+            check_pos(&a);
 
-        for i in 0..self.len() {
-            self.digits[i] = self.digits[i + bytes_shift];
+            assert_eq!(a, big_a);
         }
+    }
 
-        if shift == 0 {
-            self.pos -= bytes_shift;
-            return self;
-        }
+    #[test]
+    fn toggle_bit() {
+        for (mut a, b) in get_bit_manipulation_test_cases() {
+            let mut big_a: BignumFast<N> = BignumFast::from(a);
+            check_pos(&big_a);
+            big_a.toggle_bit(b);
+            check_pos(&big_a);
 
-        let mut carry = 0;
-        for i in (0..self.len()).rev() {
-            let tmp_carry = (self.digits[i] as u16) << (8 - shift);
-            self.digits[i] >>= shift;
-            self.digits[i] |= carry;
-            carry = tmp_carry as u8;
+            a ^= 1 << b;
+            let a = BignumFast::from(a);
+            check_pos(&a);
+
+            assert_eq!(a, big_a);
         }
+    }
 
-        self.pos -= bytes_shift;
-        if self.digits[self.pos] == 0 && self.pos > 0 {
-            self.pos -= 1;
+    #[test]
+    fn mod_inverse() {
+        let three: BignumFast<N> = BignumFast::from(3u32);
+        let eleven: BignumFast<N> = BignumFast::from(11u32);
+        assert_eq!(three.mod_inverse(&eleven), Some(BignumFast::from(4u32)));
+
+        for m in 1..60u128 {
+            let big_m: BignumFast<N> = BignumFast::from(m);
+            for a in 0..2 * m {
+                let big_a: BignumFast<N> = BignumFast::from(a);
+                let res = big_a.mod_inverse(&big_m);
+
+                match (1..=m).find(|x| a * x % m == 1 % m) {
+                    Some(x) => {
+                        let res = res.unwrap();
+                        check_pos(&res);
+                        assert_eq!(res, BignumFast::from(x % m));
+                    }
+                    None => assert_eq!(res, None),
+                }
+            }
         }
 
-        self
+        let zero: BignumFast<N> = BignumFast::zero();
+        assert_eq!(three.mod_inverse(&zero), None);
     }
-}
-
-impl<const NUM_BYTES: usize> std::ops::Shl<usize> for BignumFast<NUM_BYTES> {
-    type Output = Self;
 
-    fn shl(mut self, rhs: usize) -> Self::Output {
-        let shift = (rhs % 8) as u8;
-        let mut bytes_shift = rhs / 8;
+    #[test]
+    fn batch_mod_inverse() {
+        let one: BignumFast<N> = BignumFast::from(1u32);
+
+        // 2^127 - 1 is prime
+        let p: BignumFast<N> = BignumFast::from(u128::MAX >> 1);
+        let mut values: Vec<BignumFast<N>> =
+            (0..20).map(|_| BignumFast::rand() % p.clone()).collect();
+        values[3] = BignumFast::zero();
+        values.push(p.clone());
+        values.push(p.add_ref(&BignumFast::from(5u32)));
+
+        let inverses = BignumFast::batch_mod_inverse(&values, &p);
+        assert_eq!(inverses.len(), values.len());
+        for (v, inv) in values.iter().zip(&inverses) {
+            check_pos(inv);
+            match (v.clone() % p.clone()).is_zero() {
+                true => assert!(inv.is_zero()),
+                false => assert_eq!(v.mul_mod(inv, &p), one),
+            }
+        }
 
-        if bytes_shift + self.len() > NUM_BYTES {
-            bytes_shift = 0;
+        // composite modulus, only the values sharing a factor have no inverse
+        for m in 1..60u32 {
+            let big_m: BignumFast<N> = BignumFast::from(m);
+            let values: Vec<BignumFast<N>> = (0..2 * m).map(BignumFast::from).collect();
+            let expected: Vec<BignumFast<N>> = values
+                .iter()
+                .map(|v| v.mod_inverse(&big_m).unwrap_or_default())
+                .collect();
+            assert_eq!(BignumFast::batch_mod_inverse(&values, &big_m), expected);
         }
 
-        if bytes_shift > 0 {
-            for i in (bytes_shift..self.len() + bytes_shift).rev() {
-                self.digits[i] = self.digits[i - bytes_shift];
-            }
+        assert!(BignumFast::batch_mod_inverse(&[], &p).is_empty());
+        assert_eq!(
+            BignumFast::batch_mod_inverse(&[one], &BignumFast::zero()),
+            vec![BignumFast::zero()]
+        );
+    }
 
-            for i in 0..bytes_shift {
-                self.digits[i] = 0;
+    #[test]
+    fn parse_untrusted() {
+        for (a, b) in get_arithmatik_test_cases() {
+            for s in [format!("{:#x}", a), format!("{:x}", b), format!("{:#X}", b)] {
+                let bignum: BignumFast<N> = BignumFast::parse_untrusted(&s).unwrap();
+                check_pos(&bignum);
+
+                assert_eq!(
+                    bignum,
+                    BignumFast::try_from_hex_string(&s.to_lowercase()).unwrap()
+                );
             }
         }
 
-        let mut carry = 0;
-        for i in bytes_shift..self.len() + bytes_shift {
-            let tmp_carry = (self.digits[i] as u16) >> (8 - shift);
-            self.digits[i] <<= shift;
-            self.digits[i] |= carry;
-            carry = tmp_carry as u8;
-        }
-
-        self.pos += bytes_shift;
-        if carry != 0 && self.len() < NUM_BYTES {
-            self.digits[self.len()] = carry;
-            self.pos += 1;
+        for (s, e) in [
+            ("", ParseError::Empty),
+            ("0x", ParseError::Empty),
+            ("0xzz", ParseError::InvalidDigit),
+            ("0x0x1", ParseError::InvalidDigit),
+            ("0x-1", ParseError::InvalidDigit),
+            ("0xä", ParseError::InvalidDigit),
+            (" 0x1", ParseError::InvalidDigit),
+            ("0x10000", ParseError::TooLarge),
+        ] {
+            assert_eq!(BignumFast::<2>::parse_untrusted(s), Err(e));
         }
 
-        self
+        assert_eq!(
+            BignumFast::<2>::parse_untrusted("0x0000ffff"),
+            Ok(BignumFast::from_little_endian(&[0xff, 0xff]).unwrap())
+        );
     }
-}
-
-impl<const NUM_BYTES: usize> std::ops::Add for BignumFast<NUM_BYTES> {
-    type Output = Self;
 
-    fn add(self, rhs: Self) -> Self::Output {
-        self.add_ref(&rhs)
-    }
-}
+    #[test]
+    fn from_str() {
+        let n: BignumFast<4> = "0xdeadbeef".parse().unwrap();
+        assert_eq!(n, BignumFast::from(0xdeadbeefu32));
 
-impl<const NUM_BYTES: usize> std::ops::Sub for BignumFast<NUM_BYTES> {
-    type Output = Self;
+        for (a, b) in get_arithmatik_test_cases() {
+            let bignum: BignumFast<N> = a.to_string().parse().unwrap();
+            check_pos(&bignum);
+            assert_eq!(bignum, BignumFast::from(a));
 
-    fn sub(self, rhs: Self) -> Self::Output {
-        self.sub_ref(&rhs)
-    }
-}
+            let bignum: BignumFast<N> = format!("{:#x}", b).parse().unwrap();
+            assert_eq!(bignum, BignumFast::from(b));
+        }
 
-impl<const NUM_BYTES: usize> std::ops::Mul for BignumFast<NUM_BYTES> {
-    type Output = Self;
+        let max: BignumFast<4> = "4294967295".parse().unwrap();
+        assert_eq!(max, BignumFast::from(u32::MAX));
+        assert_eq!("0".parse::<BignumFast<4>>(), Ok(BignumFast::zero()));
 
-    fn mul(self, rhs: Self) -> Self::Output {
-        self.mul_ref(&rhs)
+        for (s, e) in [
+            ("", ParseError::Empty),
+            ("0x", ParseError::Empty),
+            ("12a", ParseError::InvalidDigit),
+            ("-1", ParseError::InvalidDigit),
+            ("+1", ParseError::InvalidDigit),
+            ("0xzz", ParseError::InvalidDigit),
+            ("4294967296", ParseError::TooLarge),
+            ("0x100000000", ParseError::TooLarge),
+        ] {
+            assert_eq!(s.parse::<BignumFast<4>>(), Err(e));
+        }
     }
-}
 
-impl<const NUM_BYTES: usize> From<u128> for BignumFast<NUM_BYTES> {
-    fn from(value: u128) -> Self {
-        let mut bignum = BignumFast::new();
+    #[test]
+    fn parse_untrusted_never_panics() {
+        let alphabet: Vec<char> = "0123456789abcdefABCDEFxX -+_gzä€\u{0}".chars().collect();
+
+        // xorshift64 (https://en.wikipedia.org/wiki/Xorshift)
+        let mut state: u64 = 0x2545f4914f6cdd1d;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
 
-        let mut pos_last_non_zero = 0;
-        for i in 0..16 {
-            let e = (value >> (i * 8)) as u8;
-            if e != 0 {
-                pos_last_non_zero = i;
+        for _ in 0..10_000 {
+            let len = (next() % 40) as usize;
+            let mut s: String = (0..len)
+                .map(|_| alphabet[(next() % alphabet.len() as u64) as usize])
+                .collect();
+            if next() % 2 == 0 {
+                s.insert_str(0, "0x");
             }
-            bignum.digits[i] = e
-        }
-        bignum.pos = pos_last_non_zero;
 
-        bignum
+            let _ = BignumFast::<8>::parse_untrusted(&s);
+        }
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
-    const N: usize = 200;
+    #[test]
+    fn is_probable_prime() {
+        let primes = crate::math::primes::sieve_up_to(5000);
+        for n in 0..5000u128 {
+            let big_n: BignumFast<N> = BignumFast::from(n);
+            assert_eq!(big_n.is_probable_prime(20), primes.contains(&(n as u64)));
+        }
 
-    fn check_pos<const NUM_BYTES: usize>(bn: &BignumFast<NUM_BYTES>) {
-        let mut pos_last_non_zero = 0;
-        for (i, e) in bn.digits.iter().enumerate() {
-            if *e != 0 {
-                pos_last_non_zero = i;
-            }
+        for s in [
+            "0x1fffffffffffffff",
+            "0x1ffffffffffffffffffffff",
+            "0x7fffffffffffffffffffffffffffffff",
+        ] {
+            let big_n: BignumFast<N> = BignumFast::try_from_hex_string(s).unwrap();
+            assert!(big_n.is_probable_prime(20));
+        }
+
+        // a prime never fails a round, so one round is enough to check the 512 bit case
+        let big_n: BignumFast<N> = BignumFast::try_from_hex_string("0xd00e8de65f7c32094b732a5628cefedc35ed796b7cea6297614545df71d8dbc67ea14565534bfc9bc5f1a680239227189c2d493924a5bd64641169533201d6e5").unwrap();
+        assert!(big_n.is_probable_prime(1));
+
+        // Carmichael numbers and products of large primes
+        for n in [
+            561,
+            1105,
+            1729,
+            2465,
+            2821,
+            6601,
+            8911,
+            41041,
+            825265,
+            321197185,
+            0x1fffffffffffffff * 0x7fffffff,
+            u128::MAX,
+        ] {
+            let big_n: BignumFast<N> = BignumFast::from(n);
+            assert!(!big_n.is_probable_prime(20));
         }
+    }
 
-        assert_eq!(pos_last_non_zero, bn.pos);
+    #[test]
+    #[should_panic(expected = "Can't test a 9 byte number for primality with 16 bytes")]
+    fn is_probable_prime_too_large() {
+        let big_n: BignumFast<16> = BignumFast::from(u64::MAX as u128 + 2);
+        big_n.is_probable_prime(20);
     }
 
-    fn get_arithmatik_test_cases() -> Vec<(u128, u128)> {
-        let mut test_cases: Vec<(u128, u128)> = vec![(0, 0xa), (0xa, 0), (0, 0)];
-        for a in (0..0xabcedef).step_by(5_000_000) {
-            for b in (0..0xabcedef).step_by(5_000_000) {
-                test_cases.push((a, b));
+    #[test]
+    fn increment_decrement_clone() {
+        let one: BignumFast<N> = BignumFast::from(1u32);
+
+        let mut test_cases: Vec<u128> = vec![1, 0xff, 0x100, 0x101, 0xffff, 0x10000, 0x1ff00];
+        for i in 1..16 {
+            test_cases.push(1 << (8 * i));
+            test_cases.push((1 << (8 * i)) - 1);
+            test_cases.push((1 << (8 * i)) + 1);
+        }
+        for (a, _) in get_arithmatik_test_cases() {
+            if a != 0 {
+                test_cases.push(a);
             }
         }
 
-        test_cases
-    }
+        for a in test_cases {
+            let big_a: BignumFast<N> = BignumFast::from(a);
 
-    fn get_bit_manipulation_test_cases() -> Vec<(u128, usize)> {
-        let base = 0xabcedef;
-        let mut test_cases: Vec<(u128, usize)> = vec![];
-        for i in 0..127 {
-            test_cases.push((base, i));
+            let inc = big_a.increment_clone();
+            check_pos(&inc);
+            assert_eq!(inc, big_a.add_ref(&one));
+
+            let dec = big_a.decrement_clone();
+            check_pos(&dec);
+            assert_eq!(dec, big_a.sub_ref(&one));
         }
 
-        test_cases
+        let zero: BignumFast<N> = BignumFast::zero();
+        assert_eq!(zero.increment_clone(), one);
     }
 
     #[test]
-    fn hex_string() {
-        for (s, p) in [
-            ("0x11abcdef", 3),
-            ("0x1abcdef", 3),
-            ("0xabcdef", 2),
-            ("0x1cdef", 2),
-            ("0xcdef", 1),
-            ("0xdef", 1),
-            ("0xef", 0),
-            ("0xf", 0),
-            ("0x0", 0),
-            ("0x1", 0),
-        ] {
-            let bignum: BignumFast<N> = BignumFast::try_from_hex_string(s).unwrap();
-            check_pos(&bignum);
+    fn conditional_swap_select() {
+        for i in 0..20 {
+            // different lengths so that the positions differ as well
+            let a: BignumFast<N> = BignumFast::rand() >> (i * 37 % (N * 8));
+            let b: BignumFast<N> = BignumFast::rand();
 
-            assert_eq!(p, bignum.pos);
-            assert_eq!(s, bignum.to_hex_string());
+            let (mut x, mut y) = (a.clone(), b.clone());
+            BignumFast::conditional_swap(&mut x, &mut y, false);
+            assert_eq!((&x, &y), (&a, &b));
+
+            BignumFast::conditional_swap(&mut x, &mut y, true);
+            assert_eq!((&x, &y), (&b, &a));
+            check_pos(&x);
+            check_pos(&y);
+
+            assert_eq!(BignumFast::conditional_select(&a, &b, false), a);
+            assert_eq!(BignumFast::conditional_select(&a, &b, true), b);
         }
     }
 
     #[test]
-    fn hex_string_2() {
-        for (a, b) in get_arithmatik_test_cases() {
-            let s = format!("{:#02x}", a);
+    fn max_value_capacity_bits() {
+        let max: BignumFast<N> = BignumFast::max_value();
+        check_pos(&max);
+
+        assert_eq!(BignumFast::<N>::capacity_bits(), N * 8);
+        assert_eq!(max.bit_length(), BignumFast::<N>::capacity_bits());
+        assert_eq!(max.count_ones() as usize, N * 8);
+        assert_eq!(BignumFast::<16>::max_value(), BignumFast::from(u128::MAX));
+
+        assert_eq!(max.checked_add(&BignumFast::from(1u32)), None);
+        assert_eq!(max.checked_mul(&BignumFast::from(2u32)), None);
+        assert_eq!(max.decrement_clone().increment_clone(), max);
+    }
 
-            let bignum: BignumFast<N> = BignumFast::try_from_hex_string(&s).unwrap();
-            check_pos(&bignum);
+    #[test]
+    #[should_panic(expected = "overflow")]
+    fn max_value_plus_one() {
+        let _ = BignumFast::<N>::max_value() + BignumFast::from(1u32);
+    }
 
-            let bignum2: BignumFast<N> = BignumFast::from(a);
-            assert_eq!(bignum, bignum2);
+    #[test]
+    fn set_zero_is_one() {
+        let mut a: BignumFast<N> = BignumFast::from(u128::MAX);
+        assert!(!a.is_one());
+
+        a.set_zero();
+        check_pos(&a);
+        assert!(a.is_zero());
+        assert_eq!(a.digits.len(), N);
+        assert_eq!(a, BignumFast::zero());
+
+        a.inc();
+        assert!(a.is_one());
+        assert!(!BignumFast::<N>::zero().is_one());
+        assert!(!BignumFast::<N>::from(0x101u32).is_one());
+        assert!(!BignumFast::<N>::from(2u32).is_one());
+    }
 
-            let s = format!("{:#02x}", b);
+    #[test]
+    fn inc_dec() {
+        let mut a: BignumFast<N> = BignumFast::from(u64::MAX);
+        assert_eq!(a.pos, 7);
+
+        a.inc();
+        check_pos(&a);
+        assert_eq!(a.pos, 8);
+        assert_eq!(a, BignumFast::from(1u128 << 64));
+
+        a.dec();
+        check_pos(&a);
+        assert_eq!(a.pos, 7);
+        assert_eq!(a, BignumFast::from(u64::MAX));
+
+        let mut b: BignumFast<N> = BignumFast::zero();
+        for i in 1..=0x1_0100u32 {
+            b.inc();
+            assert_eq!(b, BignumFast::from(i));
+        }
+        for i in (0..0x1_0100u32).rev() {
+            b.dec();
+            check_pos(&b);
+            assert_eq!(b, BignumFast::from(i));
+        }
+    }
 
-            let bignum: BignumFast<N> = BignumFast::try_from_hex_string(&s).unwrap();
-            check_pos(&bignum);
+    #[test]
+    #[should_panic]
+    fn decrement_clone_panic() {
+        let zero: BignumFast<N> = BignumFast::zero();
+        let _ = zero.decrement_clone();
+    }
 
-            let bignum2: BignumFast<N> = BignumFast::from(b);
-            assert_eq!(bignum, bignum2);
-        }
+    #[test]
+    #[should_panic]
+    fn increment_clone_panic() {
+        let max: BignumFast<2> = BignumFast::from_big_endian(&[0xff, 0xff]).unwrap();
+        let _ = max.increment_clone();
     }
 
     #[test]
-    fn len() {
-        for (s, l) in [
-            ("0x11abcdef", 4),
-            ("0x1abcdef", 4),
-            ("0xabcdef", 3),
-            ("0x1cdef", 3),
-            ("0xcdef", 2),
-            ("0xdef", 2),
-            ("0xef", 1),
-            ("0xf", 1),
-            ("0x0", 1),
-            ("0x1", 1),
-        ] {
-            let bignum: BignumFast<N> = BignumFast::try_from_hex_string(s).unwrap();
-            check_pos(&bignum);
+    fn pow_mod_with() {
+        let strategies = [
+            ReductionStrategy::LongDivision,
+            ReductionStrategy::Barrett,
+            ReductionStrategy::Montgomery,
+        ];
+
+        for m in (1..300u128).step_by(7) {
+            for a in (0..300u128).step_by(13) {
+                for e in [0u32, 1, 2, 3, 17, 65] {
+                    let res = BignumFast::<N>::from((1..=e).fold(1 % m, |acc, _| acc * a % m));
+
+                    for strategy in strategies {
+                        if strategy == ReductionStrategy::Montgomery && m % 2 == 0 {
+                            continue;
+                        }
+                        let big_a: BignumFast<N> = BignumFast::from(a);
+                        let big_e: BignumFast<N> = BignumFast::from(e as u128);
+                        let big_m: BignumFast<N> = BignumFast::from(m);
+
+                        let res_big = big_a.pow_mod_with(big_e, &big_m, strategy);
+                        check_pos(&res_big);
+                        assert_eq!(res, res_big);
+                    }
+                }
+            }
+        }
 
-            println!("{:?} {}", &bignum.digits[0..7], bignum.pos);
-            assert_eq!(l, bignum.len());
+        let m: BignumFast<N> = BignumFast::try_from_hex_string(
+            "0xd00e8de65f7c32094b732a5628cefedc35ed796b7cea6297614545df71d8dbc7",
+        )
+        .unwrap();
+        for _ in 0..5 {
+            let a = BignumFast::rand_below(&m);
+            let e = BignumFast::rand_below(&m);
+
+            let res = a.clone().pow_mod(e.clone(), &m);
+            for strategy in strategies {
+                assert_eq!(res, a.clone().pow_mod_with(e.clone(), &m, strategy));
+            }
         }
     }
 
     #[test]
-    fn pos() {
-        for (s, p) in [
-            ("0x11abcdef", 3),
-            ("0x1abcdef", 3),
-            ("0xabcdef", 2),
-            ("0x1cdef", 2),
-            ("0xcdef", 1),
-            ("0xdef", 1),
-            ("0xef", 0),
-            ("0xf", 0),
-            ("0x0", 0),
-            ("0x1", 0),
-        ] {
-            let bignum: BignumFast<N> = BignumFast::try_from_hex_string(s).unwrap();
-            check_pos(&bignum);
+    #[should_panic]
+    fn pow_mod_with_montgomery_even_modulus() {
+        let a: BignumFast<N> = BignumFast::from(3u32);
+        let _ = a.pow_mod_with(
+            BignumFast::from(5u32),
+            &BignumFast::from(10u32),
+            ReductionStrategy::Montgomery,
+        );
+    }
 
-            println!("{} {:?} {}", s, &bignum.digits[0..7], bignum.pos);
-            assert_eq!(p, bignum.pos);
+    #[test]
+    fn gcd_lcm() {
+        fn gcd(a: u128, b: u128) -> u128 {
+            match b {
+                0 => a,
+                _ => gcd(b, a % b),
+            }
+        }
+
+        let mut test_cases = get_arithmatik_test_cases();
+        test_cases.extend([
+            (18, 24),
+            (12375, 8975),
+            (0xaabbcc, 0xddeeff),
+            (0xaabb, 0xddee),
+            (17, 31),
+            (0x10001, 0xfffffffe),
+            (1 << 40, 1 << 72),
+            (3 << 40, 9 << 20),
+        ]);
+
+        for (a, b) in test_cases {
+            let big_a: BignumFast<N> = BignumFast::from(a);
+            let big_b: BignumFast<N> = BignumFast::from(b);
+
+            let res = big_a.gcd(&big_b);
+            check_pos(&res);
+            assert_eq!(res, BignumFast::from(gcd(a, b)));
+
+            let res = big_a.lcm(&big_b);
+            check_pos(&res);
+            let lcm = match gcd(a, b) {
+                0 => 0,
+                g => a / g * b,
+            };
+            assert_eq!(res, BignumFast::from(lcm));
         }
+
+        let a: BignumFast<N> = BignumFast::from(0xaabbccu32);
+        let b: BignumFast<N> = BignumFast::from(0xddeeffu32);
+        assert_eq!(a.gcd(&b), BignumFast::from(0x99u32));
     }
 
     #[test]
-    fn from_little_endian() {
-        for (e, s) in [
-            (vec![0, 0], "0x0"),
-            (vec![1, 0], "0x1"),
-            (vec![0, 1, 0], "0x100"),
-            (vec![0, 1, 2, 0], "0x20100"),
-            (vec![0, 1, 2, 3, 0], "0x3020100"),
-            (vec![0, 1, 2, 3, 4, 0], "0x403020100"),
-        ] {
-            let bignum: BignumFast<N> = BignumFast::from_little_endian(&e).unwrap();
-            check_pos(&bignum);
+    fn pow_mod_matches_reference() {
+        // square-and-multiply on a shifted copy of the exponent
+        fn pow_mod_reference<const M: usize>(
+            mut base: BignumFast<M>,
+            mut exp: BignumFast<M>,
+            modulus: &BignumFast<M>,
+        ) -> BignumFast<M> {
+            let mut t = BignumFast::from(1u32);
+            while !exp.is_zero() {
+                if !exp.is_even() {
+                    (_, t) = t.mul_ref(&base).div_with_remainder(modulus);
+                }
+                (_, base) = base.mul_ref(&base).div_with_remainder(modulus);
+                exp = exp >> 1;
+            }
+            let (_, r) = t.div_with_remainder(modulus);
+            r
+        }
 
-            assert_eq!(bignum.to_hex_string(), s);
+        let m: BignumFast<N> = BignumFast::try_from_hex_string(
+            "0xd00e8de65f7c32094b732a5628cefedc35ed796b7cea6297614545df71d8dbc7",
+        )
+        .unwrap();
+        for _ in 0..10 {
+            let a = BignumFast::rand_below(&m);
+            let e = BignumFast::rand_below(&m);
+
+            let res = a.clone().pow_mod(e.clone(), &m);
+            check_pos(&res);
+            assert_eq!(res, pow_mod_reference(a, e, &m));
         }
+
+        let m: BignumFast<N> = BignumFast::from(1u32);
+        let res = BignumFast::from(5u32).pow_mod(BignumFast::zero(), &m);
+        assert_eq!(
+            res,
+            pow_mod_reference(BignumFast::from(5u32), BignumFast::zero(), &m)
+        );
     }
 
     #[test]
-    fn from_big_endian() {
-        for (e, s) in [
-            (vec![0, 0], "0x0"),
-            (vec![1, 0], "0x100"),
-            (vec![0, 1, 0], "0x100"),
-            (vec![0, 1, 2, 0], "0x10200"),
-            (vec![0, 1, 2, 3, 0], "0x1020300"),
-            (vec![0, 1, 2, 3, 4, 0], "0x102030400"),
-        ] {
-            let bignum: BignumFast<N> = BignumFast::from_big_endian(&e).unwrap();
-            check_pos(&bignum);
+    fn isqrt() {
+        let mut test_cases: Vec<u128> = (0..1000).collect();
+        test_cases.extend((0..0xabcedef).step_by(300_000));
+        test_cases.extend([u64::MAX as u128, 1 << 100, (1 << 100) - 1, u128::MAX]);
+
+        for n in test_cases {
+            let big_n: BignumFast<N> = BignumFast::from(n);
+            let r = big_n.isqrt();
+            check_pos(&r);
 
-            assert_eq!(bignum.to_hex_string(), s);
+            let r_plus_one = r.increment_clone();
+            assert!(r.mul_ref(&r) <= big_n);
+            assert!(r_plus_one.mul_ref(&r_plus_one) > big_n);
         }
     }
 
     #[test]
-    fn from_u128() {
-        for (a, b) in get_arithmatik_test_cases() {
-            let bignum: BignumFast<N> = BignumFast::from(a);
-            check_pos(&bignum);
-            let s = format!("{:#02x}", a);
-            assert_eq!(bignum.to_hex_string(), s);
+    fn add_sub_mul_mod() {
+        for m in (1..500u128).step_by(7) {
+            let big_m: BignumFast<N> = BignumFast::from(m);
+
+            for (a, b) in get_arithmatik_test_cases().into_iter().step_by(17) {
+                let big_a: BignumFast<N> = BignumFast::from(a);
+                let big_b: BignumFast<N> = BignumFast::from(b);
+
+                let res = big_a.add_mod(&big_b, &big_m);
+                check_pos(&res);
+                assert!(res < big_m);
+                assert_eq!(res, BignumFast::from((a % m + b % m) % m));
+
+                let res = big_a.sub_mod(&big_b, &big_m);
+                check_pos(&res);
+                assert!(res < big_m);
+                assert_eq!(res, BignumFast::from((a % m + m - b % m) % m));
+
+                let res = big_a.mul_mod(&big_b, &big_m);
+                check_pos(&res);
+                assert!(res < big_m);
+                assert_eq!(res, BignumFast::from(a * b % m));
+            }
+        }
 
-            let bignum: BignumFast<N> = BignumFast::from(b);
-            check_pos(&bignum);
-            let s = format!("{:#02x}", b);
-            assert_eq!(bignum.to_hex_string(), s);
+        // operands that use all bytes are reduced before the multiplication
+        let m = 0xfffffffffffffffb_u128;
+        let big_m: BignumFast<16> = BignumFast::from(m);
+        for (a, b) in [
+            (u128::MAX, u128::MAX),
+            (u128::MAX - 12345, 1 << 127),
+            (m, u128::MAX),
+        ] {
+            let res = BignumFast::<16>::from(a).mul_mod(&BignumFast::from(b), &big_m);
+            check_pos(&res);
+            assert_eq!(res, BignumFast::from((a % m) * (b % m) % m));
         }
     }
 
     #[test]
-    fn shift_right() {
-        let base = 0xabcedef;
-        let mut test_cases: Vec<(u128, usize)> = vec![];
-        for i in 0..127 {
-            test_cases.push((base, i));
-        }
+    fn ord() {
+        let values: Vec<u128> = vec![0xabcdef, 0, 1 << 100, 7, 0xabcdee, 1 << 8, 0xff, 7];
+
+        let mut big_values: Vec<BignumFast<N>> =
+            values.iter().map(|v| BignumFast::from(*v)).collect();
+        big_values.sort();
+
+        let mut sorted = values.clone();
+        sorted.sort();
+        let sorted: Vec<BignumFast<N>> = sorted.into_iter().map(BignumFast::from).collect();
+        assert_eq!(big_values, sorted);
+
+        let set: std::collections::BTreeSet<BignumFast<N>> =
+            values.into_iter().map(BignumFast::from).collect();
+        assert_eq!(set.len(), 7);
+        assert_eq!(set.first(), Some(&BignumFast::zero()));
+        assert_eq!(set.last(), Some(&BignumFast::from(1u128 << 100)));
+    }
 
-        for (a, b) in test_cases {
+    #[test]
+    fn to_bytes() {
+        for (a, _) in get_arithmatik_test_cases() {
             let big_a: BignumFast<N> = BignumFast::from(a);
-            check_pos(&big_a);
 
-            let res: BignumFast<N> = BignumFast::from(a >> b);
-            check_pos(&res);
-            let res_big: BignumFast<N> = big_a >> b;
-            check_pos(&res_big);
+            let be = big_a.to_big_endian_bytes();
+            let le = big_a.to_little_endian_bytes();
 
-            assert_eq!(res, res_big);
+            let skip = (a.leading_zeros() as usize / 8).min(15);
+            assert_eq!(be, a.to_be_bytes()[skip..]);
+            assert_eq!(le, a.to_le_bytes()[..16 - skip]);
+
+            assert_eq!(BignumFast::from_big_endian(&be).unwrap(), big_a);
+            assert_eq!(BignumFast::from_little_endian(&le).unwrap(), big_a);
         }
+
+        let zero: BignumFast<N> = BignumFast::zero();
+        assert_eq!(zero.to_big_endian_bytes(), vec![0]);
+        assert_eq!(zero.to_little_endian_bytes(), vec![0]);
     }
 
     #[test]
-    fn shift_left() {
-        let base = 0xabcedef;
-        let mut test_cases: Vec<(u128, usize)> = vec![];
-        for i in 0..95 {
-            test_cases.push((base, i));
-        }
+    fn to_fixed_big_endian() {
+        let a: BignumFast<N> = BignumFast::from(0x0102abu32);
 
-        for (a, b) in test_cases {
-            let big_a: BignumFast<N> = BignumFast::from(a);
-            check_pos(&big_a);
+        assert_eq!(a.to_fixed_big_endian(3), Some(vec![0x01, 0x02, 0xab]));
+        assert_eq!(a.to_fixed_big_endian(5), Some(vec![0, 0, 0x01, 0x02, 0xab]));
+        assert_eq!(a.to_fixed_big_endian(2), None);
 
-            let res: BignumFast<N> = BignumFast::from(a << b);
-            check_pos(&res);
-            let res_big: BignumFast<N> = big_a << b;
-            check_pos(&res_big);
+        let zero: BignumFast<N> = BignumFast::zero();
+        assert_eq!(zero.to_fixed_big_endian(0), Some(vec![]));
+        assert_eq!(zero.to_fixed_big_endian(4), Some(vec![0; 4]));
 
-            assert_eq!(res, res_big);
-        }
+        let b = BignumFast::<N>::from_big_endian(&a.to_fixed_big_endian(N).unwrap()).unwrap();
+        assert_eq!(b, a);
     }
 
+    #[cfg(feature = "serde")]
     #[test]
-    fn addition() {
-        for (a, b) in get_arithmatik_test_cases() {
+    fn serde_roundtrip() {
+        for (a, _) in get_arithmatik_test_cases() {
             let big_a: BignumFast<N> = BignumFast::from(a);
-            check_pos(&big_a);
-            let big_b: BignumFast<N> = BignumFast::from(b);
-            check_pos(&big_b);
 
-            let res: BignumFast<N> = BignumFast::from(a + b);
-            check_pos(&res);
-            let res_big: BignumFast<N> = big_a + big_b;
-            check_pos(&res_big);
+            let json = serde_json::to_string(&big_a).unwrap();
+            assert_eq!(json, format!("\"{}\"", big_a.to_hex_string()));
 
-            assert_eq!(res, res_big);
+            let res: BignumFast<N> = serde_json::from_str(&json).unwrap();
+            check_pos(&res);
+            assert_eq!(res, big_a);
         }
+
+        let bytes = [0x01, 0x02, 0x03];
+        let de = serde::de::value::BytesDeserializer::<serde::de::value::Error>::new(&bytes);
+        let res: BignumFast<4> = serde::Deserialize::deserialize(de).unwrap();
+        assert_eq!(
+            res,
+            BignumFast::from_little_endian(&[0x03, 0x02, 0x01]).unwrap()
+        );
     }
 
+    #[cfg(feature = "serde")]
     #[test]
-    fn subtraction() {
-        for (a, b) in get_arithmatik_test_cases() {
-            let (a, b) = match a >= b {
-                true => (a, b),
-                false => (b, a),
-            };
-
-            let big_a: BignumFast<N> = BignumFast::from(a);
-            check_pos(&big_a);
-            let big_b: BignumFast<N> = BignumFast::from(b);
-            check_pos(&big_b);
+    fn serde_rejects_invalid() {
+        assert!(serde_json::from_str::<BignumFast<2>>("\"0x10000\"").is_err());
+        assert!(serde_json::from_str::<BignumFast<2>>("\"0xzz\"").is_err());
+        assert!(serde_json::from_str::<BignumFast<2>>("\"\"").is_err());
+        assert!(serde_json::from_str::<BignumFast<2>>("1").is_err());
+
+        let bytes = [0x01, 0x02, 0x03];
+        let de = serde::de::value::BytesDeserializer::<serde::de::value::Error>::new(&bytes);
+        assert!(<BignumFast<2> as serde::Deserialize>::deserialize(de).is_err());
+
+        // sequences fail as soon as they are too long, even if they never end
+        type SeqDeserializer<I> = serde::de::value::SeqDeserializer<I, serde::de::value::Error>;
+        let de = SeqDeserializer::new([0x01u8, 0x02].into_iter());
+        let res: BignumFast<2> = serde::Deserialize::deserialize(de).unwrap();
+        assert_eq!(res, BignumFast::from(0x0102u32));
+        let de = SeqDeserializer::new(core::iter::repeat(0x01u8));
+        assert!(<BignumFast<2> as serde::Deserialize>::deserialize(de).is_err());
+    }
 
-            let res: BignumFast<N> = BignumFast::from(a - b);
-            check_pos(&res);
-            let res_big: BignumFast<N> = big_a - big_b;
-            check_pos(&res_big);
+    #[test]
+    fn from_uint() {
+        for v in [0u64, 1, 0xff, 0x100, 0xffff_ffff, 0x1_0000_0000, u64::MAX] {
+            let a: BignumFast<N> = BignumFast::from(v);
+            check_pos(&a);
+            assert_eq!(a, BignumFast::from(v as u128));
+            assert_eq!(a.to_little_endian_bytes(), v.to_le_bytes()[..a.len()]);
+        }
 
-            assert_eq!(res, res_big);
+        for v in [0u32, 1, 0xff, 0x100, 0xffff, 0x1_0000, u32::MAX] {
+            let a: BignumFast<N> = BignumFast::from(v);
+            check_pos(&a);
+            assert_eq!(a, BignumFast::from(v as u128));
         }
+
+        // values only need as many bytes as they actually occupy
+        let a: BignumFast<2> = BignumFast::from(0xffffu128);
+        check_pos(&a);
+        assert_eq!(a, BignumFast::from_big_endian(&[0xff, 0xff]).unwrap());
+
+        let a: BignumFast<8> = BignumFast::from(u64::MAX);
+        check_pos(&a);
+        assert_eq!(a.pos, 7);
     }
 
     #[test]
     #[should_panic]
-    fn subtraction_panic() {
-        for (a, b) in get_arithmatik_test_cases() {
-            let (a, b) = match a >= b {
-                false => (a, b),
-                true => (b, a),
-            };
+    fn from_uint_too_large() {
+        let _: BignumFast<2> = BignumFast::from(0x10000u32);
+    }
 
-            let big_a: BignumFast<N> = BignumFast::from(a);
-            check_pos(&big_a);
-            let big_b: BignumFast<N> = BignumFast::from(b);
-            check_pos(&big_b);
+    #[test]
+    fn try_from_slice() {
+        let bytes: &[u8] = &[0x01, 0x02, 0x03];
 
-            let _res_big: BignumFast<N> = big_a - big_b;
+        let a: BignumFast<3> = BignumFast::try_from(bytes).unwrap();
+        check_pos(&a);
+        assert_eq!(a, BignumFast::from(0x010203u32));
+
+        assert_eq!(
+            BignumFast::<2>::try_from(bytes).unwrap_err(),
+            ParseError::TooLarge
+        );
+    }
+
+    #[test]
+    fn rand() {
+        let a: BignumFast<N> = BignumFast::rand();
+        let b: BignumFast<N> = BignumFast::rand();
+        check_pos(&a);
+        check_pos(&b);
+        assert_ne!(a, b);
+
+        let a: BignumFast<1> = BignumFast::rand();
+        check_pos(&a);
+    }
+
+    #[test]
+    fn rand_below() {
+        let mut bounds = vec![
+            BignumFast::from(1u32),
+            BignumFast::from(2u32),
+            BignumFast::from(0x100u32),
+            BignumFast::from(0x101u32),
+            BignumFast::max_value(),
+        ];
+        let mut half = BignumFast::<N>::zero();
+        half.set_bit(N * 8 - 1);
+        bounds.push(half.increment_clone());
+
+        for bound in bounds {
+            for _ in 0..20 {
+                let r: BignumFast<N> = BignumFast::rand_below(&bound);
+                check_pos(&r);
+                assert!(r < bound);
+            }
+        }
+
+        // values in the upper half of a full width bound show up
+        let mut upper = 0;
+        for _ in 0..64 {
+            if BignumFast::rand_below(&BignumFast::<N>::max_value()).get_bit(N * 8 - 1) {
+                upper += 1;
+            }
         }
+        assert!((8..=56).contains(&upper));
+
+        // every value of a small bound is drawn
+        let mut seen = [false; 5];
+        for _ in 0..200 {
+            let r: BignumFast<N> = BignumFast::rand_below(&BignumFast::from(5u32));
+            seen[r.to_little_endian_bytes()[0] as usize] = true;
+        }
+        assert_eq!(seen, [true; 5]);
     }
 
     #[test]
-    fn multiplication() {
-        for (a, b) in get_arithmatik_test_cases() {
-            let big_a: BignumFast<N> = BignumFast::from(a);
-            check_pos(&big_a);
-            let big_b: BignumFast<N> = BignumFast::from(b);
-            check_pos(&big_b);
+    fn bit_length() {
+        let zero: BignumFast<N> = BignumFast::zero();
+        assert_eq!(zero.bit_length(), 0);
 
-            let res: BignumFast<N> = BignumFast::from(a * b);
-            check_pos(&res);
-            let res_big: BignumFast<N> = big_a * big_b;
-            check_pos(&res_big);
+        for k in 0..N * 8 {
+            let mut a: BignumFast<N> = BignumFast::zero();
+            a.set_bit(k);
+            assert_eq!(a.bit_length(), k + 1);
 
-            assert_eq!(res, res_big);
+            let a_minus_one = a.decrement_clone();
+            assert_eq!(a_minus_one.bit_length(), k);
+        }
+
+        for (a, _) in get_arithmatik_test_cases() {
+            let big_a: BignumFast<N> = BignumFast::from(a);
+            assert_eq!(big_a.bit_length(), 128 - a.leading_zeros() as usize);
         }
     }
 
     #[test]
-    fn division_with_remainder() {
-        for (a, b) in get_arithmatik_test_cases() {
-            if b == 0 {
+    fn trailing_zeros_is_power_of_two() {
+        let zero: BignumFast<N> = BignumFast::zero();
+        assert_eq!(zero.trailing_zeros(), N * 8);
+        assert!(!zero.is_power_of_two());
+
+        let a: BignumFast<N> = BignumFast::from(0x1000u32);
+        assert_eq!(a.trailing_zeros(), 12);
+        assert!(a.is_power_of_two());
+
+        let a: BignumFast<N> = BignumFast::from(0x1001u32);
+        assert_eq!(a.trailing_zeros(), 0);
+        assert!(!a.is_power_of_two());
+
+        for (a, _) in get_arithmatik_test_cases() {
+            if a == 0 {
                 continue;
             }
             let big_a: BignumFast<N> = BignumFast::from(a);
-            check_pos(&big_a);
-            let big_b: BignumFast<N> = BignumFast::from(b as u128);
-            check_pos(&big_b);
+            assert_eq!(big_a.trailing_zeros(), a.trailing_zeros() as usize);
+            assert_eq!(big_a.is_power_of_two(), a.is_power_of_two());
+        }
 
-            let (big_q, big_r) = BignumFast::div_with_remainder(&big_a, &big_b);
-            check_pos(&big_q);
-            check_pos(&big_r);
-            let q: BignumFast<N> = BignumFast::from(a / b);
-            let r: BignumFast<N> = BignumFast::from(a % b);
-            check_pos(&q);
-            check_pos(&r);
+        for k in 0..N * 8 {
+            let mut a: BignumFast<N> = BignumFast::zero();
+            a.set_bit(k);
+            assert_eq!(a.trailing_zeros(), k);
+            assert!(a.is_power_of_two());
 
-            assert_eq!(big_q, q);
-            assert_eq!(big_r, r);
+            a.set_bit(N * 8 - 1);
+            assert_eq!(a.trailing_zeros(), k);
+            assert_eq!(a.is_power_of_two(), k == N * 8 - 1);
         }
     }
 
     #[test]
-    fn comparison() {
-        for (a, b) in get_arithmatik_test_cases() {
-            let big_a: BignumFast<N> = BignumFast::from(a);
-            let big_b: BignumFast<N> = BignumFast::from(b);
-            check_pos(&big_a);
-            check_pos(&big_b);
+    fn count_ones() {
+        let a: BignumFast<N> = BignumFast::from(0xffu32);
+        assert_eq!(a.count_ones(), 8);
+        assert_eq!(BignumFast::<N>::zero().count_ones(), 0);
 
-            let res = a.eq(&b);
-            let res_big = big_a.eq(&big_b);
-            assert_eq!(res, res_big);
-
-            let res = a.lt(&b);
-            let res_big = big_a.lt(&big_b);
-            assert_eq!(res, res_big);
+        for _ in 0..20 {
+            let a: BignumFast<N> = BignumFast::rand();
+            let naive = (0..N * 8).filter(|i| a.get_bit(*i)).count() as u32;
+            assert_eq!(a.count_ones(), naive);
+        }
+    }
 
-            let res = a.le(&b);
-            let res_big = big_a.le(&big_b);
-            assert_eq!(res, res_big);
+    #[test]
+    fn hash_map_keys() {
+        use std::collections::HashMap;
 
-            let res = a.gt(&b);
-            let res_big = big_a.gt(&big_b);
-            assert_eq!(res, res_big);
+        let mut map: HashMap<BignumFast<N>, u32> = HashMap::new();
+        for a in 0..100u32 {
+            map.insert(BignumFast::from(a * 0x10001), a);
+        }
 
-            let res = a.ge(&b);
-            let res_big = big_a.ge(&big_b);
-            assert_eq!(res, res_big);
+        for a in 0..100u32 {
+            // same values built from bytes with leading zeros and from a division that
+            // leaves the bytes above pos untouched
+            let from_bytes =
+                BignumFast::from_big_endian(&[&[0; 8], &(a * 0x10001).to_be_bytes()[..]].concat())
+                    .unwrap();
+            let (from_div, _) = BignumFast::from((a as u128 * 0x10001) << 64)
+                .div_with_remainder(&(BignumFast::from(1u32) << 64));
 
-            let res = a.partial_cmp(&b);
-            let res_big = big_a.partial_cmp(&big_b);
-            assert_eq!(res, res_big);
+            assert_eq!(map.get(&from_bytes), Some(&a));
+            assert_eq!(map.get(&from_div), Some(&a));
         }
+        assert_eq!(map.get(&BignumFast::from(0x10000u32)), None);
     }
 
     #[test]
-    fn get_bit() {
-        for (a, b) in get_bit_manipulation_test_cases() {
-            let big_a: BignumFast<N> = BignumFast::from(a);
-            check_pos(&big_a);
+    fn bits_iterators() {
+        let zero: BignumFast<N> = BignumFast::zero();
+        assert_eq!(zero.bits().count(), 0);
+        assert_eq!(zero.bits_be().count(), 0);
+
+        let a: BignumFast<N> = BignumFast::from(0b1011_0000_0001u32);
+        assert_eq!(
+            a.bits_be().collect::<Vec<bool>>(),
+            [true, false, true, true, false, false, false, false, false, false, false, true]
+        );
+
+        for _ in 0..20 {
+            let a: BignumFast<N> = BignumFast::rand();
+            assert_eq!(a.bits().count(), a.bit_length());
+
+            let mut le = BignumFast::zero();
+            for (i, bit) in a.bits().enumerate() {
+                if bit {
+                    le.set_bit(i);
+                }
+            }
 
-            let res = (a >> b) & 1 == 1;
-            let res_big = big_a.get_bit(b);
+            let mut be = BignumFast::zero();
+            for bit in a.bits_be() {
+                be = be << 1;
+                if bit {
+                    be.set_bit(0);
+                }
+            }
 
-            assert_eq!(res, res_big);
+            assert_eq!(le, a);
+            assert_eq!(be, a);
+            check_pos(&le);
+            check_pos(&be);
         }
     }
 
     #[test]
-    fn set_bit() {
-        for (mut a, b) in get_bit_manipulation_test_cases() {
-            let mut big_a: BignumFast<N> = BignumFast::from(a);
-            check_pos(&big_a);
-            big_a.set_bit(b);
-            check_pos(&big_a);
+    fn checked_arithmetic() {
+        let values: Vec<u32> = (0..=0xffff)
+            .step_by(1031)
+            .chain([1, 0xff, 0x100, 0xffff])
+            .collect();
+
+        for a in values.iter() {
+            for b in values.iter() {
+                let big_a: BignumFast<2> = BignumFast::from(*a);
+                let big_b: BignumFast<2> = BignumFast::from(*b);
+
+                let res = big_a.checked_add(&big_b);
+                assert_eq!(res.is_none(), a + b > 0xffff);
+                assert_eq!(
+                    res.is_none(),
+                    std::panic::catch_unwind(|| big_a.add_ref(&big_b)).is_err()
+                );
+                if let Some(res) = res {
+                    check_pos(&res);
+                    assert_eq!(res, BignumFast::from(a + b));
+                }
 
-            a |= 1 << b;
-            let a = BignumFast::from(a);
-            check_pos(&a);
+                let res = big_a.checked_sub(&big_b);
+                assert_eq!(res.is_none(), a < b);
+                if let Some(res) = res {
+                    check_pos(&res);
+                    assert_eq!(res, BignumFast::from(a - b));
+                }
 
-            assert_eq!(a, big_a);
+                let res = big_a.checked_mul(&big_b);
+                assert_eq!(res.is_none(), a * b > 0xffff);
+                assert_eq!(
+                    res.is_none(),
+                    std::panic::catch_unwind(|| big_a.mul_ref(&big_b)).is_err()
+                );
+                if let Some(res) = res {
+                    check_pos(&res);
+                    assert_eq!(res, BignumFast::from(a * b));
+                }
+            }
         }
     }
 
     #[test]
-    fn unset_bit() {
-        for (mut a, b) in get_bit_manipulation_test_cases() {
-            let mut big_a: BignumFast<N> = BignumFast::from(a);
-            check_pos(&big_a);
-            big_a.unset_bit(b);
-            check_pos(&big_a);
+    fn from_endian_odd_lengths() {
+        for len in [1, 7, 9, 20, N] {
+            let bytes: Vec<u8> = (1..=len as u8).collect();
 
-            a &= !(1 << b);
-            let a = BignumFast::from(a);
-            check_pos(&a);
+            let be: BignumFast<N> = BignumFast::from_big_endian(&bytes).unwrap();
+            check_pos(&be);
+            assert_eq!(be.to_big_endian_bytes(), bytes);
 
-            assert_eq!(a, big_a);
+            let le: BignumFast<N> = BignumFast::from_little_endian(&bytes).unwrap();
+            check_pos(&le);
+            assert_eq!(le.to_little_endian_bytes(), bytes);
         }
+
+        let a: BignumFast<N> = BignumFast::from_big_endian(&[0xab]).unwrap();
+        assert_eq!(a, BignumFast::from(0xabu32));
+
+        let a: BignumFast<N> = BignumFast::from_big_endian(&[1, 2, 3, 4, 5, 6, 7]).unwrap();
+        assert_eq!(a, BignumFast::from(0x01020304050607u64));
+
+        let a: BignumFast<N> =
+            BignumFast::from_little_endian(&[1, 2, 3, 4, 5, 6, 7, 8, 9]).unwrap();
+        assert_eq!(a, BignumFast::from(0x090807060504030201u128));
+
+        // leading zeros don't count towards pos
+        let a: BignumFast<9> = BignumFast::from_big_endian(&[0, 0, 0, 0, 0, 0, 0, 0, 1]).unwrap();
+        check_pos(&a);
+        assert_eq!(a, BignumFast::from(1u32));
+
+        assert!(BignumFast::<8>::from_big_endian(&[0; 9]).is_none());
+        assert!(BignumFast::<8>::from_little_endian(&[0; 9]).is_none());
     }
 
     #[test]
-    fn toggle_bit() {
-        for (mut a, b) in get_bit_manipulation_test_cases() {
-            let mut big_a: BignumFast<N> = BignumFast::from(a);
-            check_pos(&big_a);
-            big_a.toggle_bit(b);
-            check_pos(&big_a);
+    fn pow() {
+        let three: BignumFast<N> = BignumFast::from(3u32);
+        assert_eq!(
+            three.clone().pow(BignumFast::from(7u32)),
+            BignumFast::from(2187u32)
+        );
+        assert_eq!(three.pow(BignumFast::zero()), BignumFast::from(1u32));
+        assert_eq!(
+            BignumFast::<N>::zero().pow(BignumFast::zero()),
+            BignumFast::from(1u32)
+        );
+
+        for (a, b) in [
+            (2u128, 127u32),
+            (0xff, 16),
+            (0xabcdef, 5),
+            (12345, 0),
+            (0, 9),
+            (1, 1000),
+        ] {
+            let big: BignumFast<N> = BignumFast::from(a).pow(BignumFast::from(b));
+            check_pos(&big);
 
-            a ^= 1 << b;
-            let a = BignumFast::from(a);
+            let slow = UnsignedBignum::from(a).pow(UnsignedBignum::from(b as u128));
+            assert_eq!(big.to_hex_string(), slow.to_hex_string());
+        }
+
+        // exactly fits
+        let a: BignumFast<4> = BignumFast::from(0xffffu32);
+        assert_eq!(
+            a.pow(BignumFast::from(2u32)),
+            BignumFast::from(0xfffe0001u32)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit")]
+    fn pow_overflow() {
+        let a: BignumFast<4> = BignumFast::from(0x10000u32);
+        let _ = a.pow(BignumFast::from(2u32));
+    }
+
+    #[test]
+    fn pow_mod_modulus_one() {
+        let one: BignumFast<N> = BignumFast::from(1u32);
+        let strategies = [
+            ReductionStrategy::LongDivision,
+            ReductionStrategy::Barrett,
+            ReductionStrategy::Montgomery,
+        ];
+
+        for x in [0u128, 1, 2, 0xabcdef, u128::MAX] {
+            for e in [0u128, 1, 2, 65537] {
+                let res = BignumFast::<N>::from(x).pow_mod(BignumFast::from(e), &one);
+                check_pos(&res);
+                assert!(res.is_zero());
+
+                for strategy in strategies {
+                    let res =
+                        BignumFast::<N>::from(x).pow_mod_with(BignumFast::from(e), &one, strategy);
+                    assert!(res.is_zero());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn from_boundaries_pos() {
+        for (v, p) in [
+            (0u128, 0),
+            (1, 0),
+            (u64::MAX as u128, 7),
+            (u64::MAX as u128 + 1, 8),
+            (u128::MAX, 15),
+        ] {
+            let a: BignumFast<N> = BignumFast::from(v);
             check_pos(&a);
+            assert_eq!(a.pos, p);
+        }
 
-            assert_eq!(a, big_a);
+        for (v, p) in [(0u64, 0), (1, 0), (0x100, 1), (u64::MAX, 7)] {
+            let a: BignumFast<N> = BignumFast::from(v);
+            check_pos(&a);
+            assert_eq!(a.pos, p);
         }
     }
+
+    #[test]
+    fn random_prime() {
+        for bits in [2, 3, 8, 13, 64, 100] {
+            let p: BignumFast<N> = BignumFast::random_prime(bits);
+            check_pos(&p);
+            assert_eq!(p.bit_length(), bits);
+            assert!(p.get_bit(bits - 2));
+            assert!(p.is_probable_prime(10));
+        }
+
+        // 64 bits are 8 bytes, squares need 16
+        let p: BignumFast<16> = BignumFast::random_prime(64);
+        assert_eq!(p.bit_length(), 64);
+    }
+
+    #[test]
+    #[should_panic(expected = "Can't create a 65 bit prime with 16 bytes")]
+    fn random_prime_too_large() {
+        let _: BignumFast<16> = BignumFast::random_prime(65);
+    }
 }
@@ -0,0 +1,178 @@
+use crate::math::bignum_fast::BignumFast;
+
+/// Number of increments `c` of `x^2 + c` tried before `pollard_rho` gives up
+#[cfg(any(feature = "getrandom", all(feature = "std", unix)))]
+const RHO_ATTEMPTS: u32 = 20;
+
+/// Pollard's rho algorithm (https://en.wikipedia.org/wiki/Pollard%27s_rho_algorithm)
+///
+/// Iterates `x -> x^2 + c mod n` starting at `x = 2` with Floyd's cycle detection. `c = 1` is
+/// tried first and the next `c` is used if the cycle closes without a factor. Returns a
+/// nontrivial factor, or `None` if `n` is prime, smaller than 4 or no factor was found.
+///
+/// `NUM_BYTES` has to be at least twice the size of `n` in bytes, so that squares mod n fit
+#[cfg(any(feature = "getrandom", all(feature = "std", unix)))]
+pub fn pollard_rho<const NUM_BYTES: usize>(
+    n: &BignumFast<NUM_BYTES>,
+) -> Option<BignumFast<NUM_BYTES>> {
+    if *n < BignumFast::from(4u32) {
+        return None;
+    }
+    if n.is_even() {
+        return Some(BignumFast::from(2u32));
+    }
+    if n.is_probable_prime(20) {
+        return None;
+    }
+
+    let one = BignumFast::from(1u32);
+    let f = |x: &BignumFast<NUM_BYTES>, c: &BignumFast<NUM_BYTES>| x.mul_mod(x, n).add_mod(c, n);
+
+    for c in 1..=RHO_ATTEMPTS {
+        let c = BignumFast::from(c);
+        let mut x = BignumFast::from(2u32);
+        let mut y = x.clone();
+
+        let d = loop {
+            x = f(&x, &c);
+            y = f(&f(&y, &c), &c);
+
+            let diff = match x >= y {
+                true => x.sub_ref(&y),
+                false => y.sub_ref(&x),
+            };
+            let d = diff.gcd(n);
+            if d != one {
+                break d;
+            }
+        };
+
+        if d != *n {
+            return Some(d);
+        }
+    }
+
+    None
+}
+
+/// Fermat's factorization method (https://en.wikipedia.org/wiki/Fermat%27s_factorization_method)
+///
+/// Searches `a = ceil(sqrt(n)), a + 1, ...` for `a^2 - n = b^2`, then `n = (a - b)(a + b)`.
+/// This is fast if `n` has two factors close to `sqrt(n)`. Returns `(p, q)` with `p <= q` and
+/// `1 < p`, or `None` if no factorization was found within `max_iterations` steps.
+///
+/// `NUM_BYTES` has to be at least twice the size of `n` in bytes, so that `a^2` fits
+pub fn fermat_factor<const NUM_BYTES: usize>(
+    n: &BignumFast<NUM_BYTES>,
+    max_iterations: usize,
+) -> Option<(BignumFast<NUM_BYTES>, BignumFast<NUM_BYTES>)> {
+    let two = BignumFast::from(2u32);
+    if *n < BignumFast::from(4u32) {
+        return None;
+    }
+    if n.is_even() {
+        return Some((two.clone(), n.clone() >> 1));
+    }
+
+    let mut a = n.isqrt();
+    if a.mul_ref(&a) < *n {
+        a.inc();
+    }
+
+    for _ in 0..max_iterations {
+        let b2 = a.mul_ref(&a).sub_ref(n);
+        let b = b2.isqrt();
+
+        if b.mul_ref(&b) == b2 {
+            let p = a.sub_ref(&b);
+            // a - b = 1 is the trivial factorization 1 * n
+            return match p < two {
+                true => None,
+                false => Some((p, a.add_ref(&b))),
+            };
+        }
+        a.inc();
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const N: usize = 32;
+
+    #[test]
+    fn pollard_rho_small_composites() {
+        for (p, q) in [
+            (0xabcdu64, 0xbeefu64),
+            (3, 5),
+            (65521, 65537),
+            (4294967291, 4294967279),
+            (7, 7),
+        ] {
+            let n: BignumFast<N> = BignumFast::from(p * q);
+            let d = pollard_rho(&n).unwrap();
+
+            assert!(d > BignumFast::from(1u32) && d < n);
+            let (cofactor, r) = n.div_with_remainder(&d);
+            assert!(r.is_zero());
+            assert_eq!(d.mul_ref(&cofactor), n);
+        }
+
+        assert_eq!(
+            pollard_rho(&BignumFast::<N>::from(1000u32)),
+            Some(BignumFast::from(2u32))
+        );
+    }
+
+    #[test]
+    fn pollard_rho_no_factor() {
+        for n in [0u64, 1, 2, 3, 5, 65537, 4294967291] {
+            assert_eq!(pollard_rho(&BignumFast::<N>::from(n)), None);
+        }
+    }
+
+    #[test]
+    fn fermat_factor_close_primes() {
+        let n: BignumFast<N> = BignumFast::from(65521u64 * 65537);
+        assert_eq!(
+            fermat_factor(&n, 10),
+            Some((BignumFast::from(65521u32), BignumFast::from(65537u32)))
+        );
+
+        let n: BignumFast<N> = BignumFast::from(0xabcdu64 * 0xbeef);
+        let (p, q) = fermat_factor(&n, 10_000).unwrap();
+        assert!(p > BignumFast::from(1u32) && p <= q);
+        assert_eq!(p.mul_ref(&q), n);
+
+        // perfect square
+        let n: BignumFast<N> = BignumFast::from(4294967291u64 * 4294967291);
+        assert_eq!(
+            fermat_factor(&n, 1),
+            Some((
+                BignumFast::from(4294967291u32),
+                BignumFast::from(4294967291u32)
+            ))
+        );
+    }
+
+    #[test]
+    fn fermat_factor_no_factor() {
+        // primes only have the trivial factorization a - b = 1
+        assert_eq!(
+            fermat_factor(&BignumFast::<N>::from(65537u32), 100_000),
+            None
+        );
+        assert_eq!(fermat_factor(&BignumFast::<N>::from(3u32), 10), None);
+        assert_eq!(
+            fermat_factor(&BignumFast::<N>::from(10u32), 10),
+            Some((BignumFast::from(2u32), BignumFast::from(5u32)))
+        );
+
+        // factors far apart are not found within a few iterations
+        let n: BignumFast<N> = BignumFast::from(3u64 * 4294967291);
+        assert_eq!(fermat_factor(&n, 10), None);
+    }
+}
@@ -1,4 +1,6 @@
 pub mod bignum_fast;
+pub mod factor;
+pub mod primes;
 pub mod signed_bignum;
 pub mod unsigned_bignum;
 pub mod utils;
@@ -0,0 +1,139 @@
+use alloc::{vec, vec::Vec};
+
+/// Upper bound for the cached table returned by `small_primes`
+const SMALL_PRIMES_LIMIT: u64 = 2048;
+
+/// Sieve of Eratosthenes (https://en.wikipedia.org/wiki/Sieve_of_Eratosthenes)
+/// returns all primes `p <= limit` in ascending order
+pub fn sieve_up_to(limit: u64) -> Vec<u64> {
+    if limit < 2 {
+        return vec![];
+    }
+
+    let limit = limit as usize;
+    let mut is_composite = vec![false; limit + 1];
+
+    let mut i = 2;
+    while i * i <= limit {
+        if !is_composite[i] {
+            for j in (i * i..=limit).step_by(i) {
+                is_composite[j] = true;
+            }
+        }
+        i += 1;
+    }
+
+    (2..=limit)
+        .filter(|i| !is_composite[*i])
+        .map(|i| i as u64)
+        .collect()
+}
+
+/// Returns the first `n` primes in ascending order
+pub fn first_n(n: usize) -> Vec<u64> {
+    if n == 0 {
+        return vec![];
+    }
+
+    // p_n < n * (ln n + ln ln n) for n >= 6 (Rosser's theorem), bounded from above with
+    // log2 so no floating point (and std) is needed
+    let limit = if n < 6 {
+        13
+    } else {
+        let log = n.ilog2() as u64 + 1;
+        n as u64 * (log + log.ilog2() as u64 + 1)
+    };
+
+    let mut primes = sieve_up_to(limit);
+    primes.truncate(n);
+    primes
+}
+
+/// Number of primes below `SMALL_PRIMES_LIMIT`
+const SMALL_PRIMES_COUNT: usize = 309;
+
+/// Sieve of Eratosthenes evaluated at compile time for the `small_primes` table
+const fn small_primes_table() -> [u64; SMALL_PRIMES_COUNT] {
+    let limit = SMALL_PRIMES_LIMIT as usize;
+    let mut is_composite = [false; SMALL_PRIMES_LIMIT as usize];
+
+    let mut i = 2;
+    while i * i < limit {
+        if !is_composite[i] {
+            let mut j = i * i;
+            while j < limit {
+                is_composite[j] = true;
+                j += i;
+            }
+        }
+        i += 1;
+    }
+
+    let mut primes = [0; SMALL_PRIMES_COUNT];
+    let mut count = 0;
+    let mut i = 2;
+    while i < limit {
+        if !is_composite[i] {
+            primes[count] = i as u64;
+            count += 1;
+        }
+        i += 1;
+    }
+    assert!(count == SMALL_PRIMES_COUNT);
+
+    primes
+}
+
+/// Table of all primes below 2048, e.g. for trial division before a primality test
+pub fn small_primes() -> &'static [u64] {
+    static SMALL_PRIMES: [u64; SMALL_PRIMES_COUNT] = small_primes_table();
+    &SMALL_PRIMES
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_prime(n: u64) -> bool {
+        n >= 2
+            && (2..n)
+                .take_while(|i| i * i <= n)
+                .all(|i| !n.is_multiple_of(i))
+    }
+
+    #[test]
+    fn first_n_test() {
+        assert_eq!(first_n(0), Vec::<u64>::new());
+        assert_eq!(first_n(1), vec![2]);
+        assert_eq!(first_n(10), vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+
+        for n in 0..500 {
+            let primes = first_n(n);
+            assert_eq!(primes.len(), n);
+            assert!(primes.iter().all(|p| is_prime(*p)));
+        }
+    }
+
+    #[test]
+    fn sieve_up_to_test() {
+        assert_eq!(sieve_up_to(0), Vec::<u64>::new());
+        assert_eq!(sieve_up_to(1), Vec::<u64>::new());
+        assert_eq!(sieve_up_to(2), vec![2]);
+        assert_eq!(sieve_up_to(30), first_n(10));
+
+        for limit in 0..1000 {
+            let correct: Vec<u64> = (0..=limit).filter(|i| is_prime(*i)).collect();
+            assert_eq!(sieve_up_to(limit), correct);
+        }
+    }
+
+    #[test]
+    fn small_primes_test() {
+        let primes = small_primes();
+
+        assert_eq!(primes.len(), 309);
+        assert_eq!(primes[0], 2);
+        assert_eq!(*primes.last().unwrap(), 2039);
+        assert_eq!(primes, sieve_up_to(SMALL_PRIMES_LIMIT - 1));
+    }
+}
@@ -1,4 +1,9 @@
-use std::io::Read;
+#[cfg(any(feature = "getrandom", all(feature = "std", unix)))]
+use crate::crypto::rng;
+use crate::math::bignum_fast::ParseError;
+use crate::math::unsigned_bignum::UnsignedBignum;
+use crate::math::utils;
+use alloc::{borrow::ToOwned, format, string::String, string::ToString, vec, vec::Vec};
 /// Internal storage in little endian
 ///
 /// 0xabcdef00 -> Bignum([0x00, 0xef, 0xcd, 0xab])
@@ -63,28 +68,9 @@ impl SignedBignum {
         format!("0x{}", res)
     }
 
-    pub fn try_from_hex_string(t: &str) -> Result<Self, std::num::ParseIntError> {
-        let s = t.trim_start_matches("0x");
-
-        let mut vec = vec![];
-
-        let len = s.len();
-        for i in 0..len / 2 {
-            let b = &s[len - (2 * i + 2)..len - 2 * i];
-            let b = u8::from_str_radix(b, 16)?;
-            vec.push(b);
-        }
-
-        if len % 2 != 0 {
-            let b = &s[0..1];
-            let b = u8::from_str_radix(b, 16)?;
-            vec.push(b);
-        }
-
-        let mut b = SignedBignum::from_little_endian(&vec);
-        b.strip();
-
-        Ok(b)
+    pub fn try_from_hex_string(t: &str) -> Result<Self, ParseError> {
+        let bytes = utils::hex_to_le_bytes(t)?;
+        Ok(SignedBignum::from_little_endian(&bytes))
     }
 
     pub fn len(&self) -> usize {
@@ -207,6 +193,17 @@ impl SignedBignum {
         (quotient, remainder)
     }
 
+    /// Euclidean remainder of `self / modulus`, always in `[0, |modulus|)` unlike the truncated
+    /// remainder of `div_with_remainder` which takes the sign of `self`
+    pub fn rem_euclid(&self, modulus: &Self) -> Self {
+        let (_, remainder) = self.div_with_remainder(modulus);
+
+        match remainder.sign {
+            true => remainder.add_ref(&modulus.abs()),
+            false => remainder,
+        }
+    }
+
     pub fn is_zero(&self) -> bool {
         self.digits.len() == 1 && self.digits[0] == 0
     }
@@ -216,7 +213,7 @@ impl SignedBignum {
     }
 
     pub fn is_even(&self) -> bool {
-        self.digits[0] % 2 == 0
+        self.digits[0].is_multiple_of(2)
     }
 
     /// Exponentiation by squaring (https://en.wikipedia.org/wiki/Exponentiation_by_squaring)
@@ -278,6 +275,11 @@ impl SignedBignum {
     }
 
     pub fn pow_mod(self, exponent: Self, modulus: &Self) -> Self {
+        // every value is congruent to 0 mod 1
+        if *modulus == Self::from(1) {
+            return Self::new();
+        }
+
         let mut base = self;
         let mut exp = exponent;
 
@@ -314,7 +316,7 @@ impl SignedBignum {
             (false, false) => Self::add_ref_internal(self, rhs),
             // (-x) + (y)  => y - x
             (true, false) => {
-                if self.eq(rhs) {
+                if self.digits == rhs.digits {
                     Self::new()
                 } else if rhs.gt_internal(self) {
                     Self::sub_ref_internal(rhs, self)
@@ -326,7 +328,7 @@ impl SignedBignum {
             }
             // (x)  + (-y) => x - y
             (false, true) => {
-                if self.eq(rhs) {
+                if self.digits == rhs.digits {
                     Self::new()
                 } else if self.gt_internal(rhs) {
                     Self::sub_ref_internal(self, rhs)
@@ -436,19 +438,103 @@ impl SignedBignum {
         res
     }
 
+    /// Encode as SSH `mpint` (RFC 4251 - Section 5): 4 byte big endian length followed by the
+    /// minimal two's complement representation in big endian
+    pub fn to_mpint(&self) -> Vec<u8> {
+        let mut data = vec![];
+
+        if !self.is_zero() {
+            data = self.digits.iter().rev().copied().collect::<Vec<u8>>();
+
+            if self.sign {
+                let mut carry = 1;
+                for b in data.iter_mut().rev() {
+                    let tmp = (!*b) as u16 + carry;
+                    carry = tmp >> 8;
+                    *b = tmp as u8;
+                }
+
+                if data[0] & 0x80 == 0 {
+                    data.insert(0, 0xff);
+                }
+                while data.len() > 1 && data[0] == 0xff && data[1] & 0x80 != 0 {
+                    data.remove(0);
+                }
+            } else if data[0] & 0x80 != 0 {
+                data.insert(0, 0x00);
+            }
+        }
+
+        let mut res = (data.len() as u32).to_be_bytes().to_vec();
+        res.extend(data);
+        res
+    }
+
+    /// Decode an SSH `mpint` (RFC 4251 - Section 5)
+    pub fn from_mpint(value: &[u8]) -> Result<Self, String> {
+        if value.len() < 4 {
+            return Err("mpint is missing the length prefix".to_owned());
+        }
+
+        let len = u32::from_be_bytes([value[0], value[1], value[2], value[3]]) as usize;
+        let data = &value[4..];
+        if data.len() != len {
+            return Err(format!(
+                "mpint length prefix is {} but {} bytes follow",
+                len,
+                data.len()
+            ));
+        }
+
+        if data.is_empty() {
+            return Ok(Self::new());
+        }
+
+        if data[0] & 0x80 == 0 {
+            return Ok(Self::from_big_endian(data));
+        }
+
+        let mut magnitude = data.to_vec();
+        let mut carry = 1;
+        for b in magnitude.iter_mut().rev() {
+            let tmp = (!*b) as u16 + carry;
+            carry = tmp >> 8;
+            *b = tmp as u8;
+        }
+
+        let mut bn = Self::from_big_endian(&magnitude);
+        bn.sign = true;
+        Ok(bn)
+    }
+
+    /// Minimal big endian representation of the magnitude, the sign is dropped
+    pub fn to_big_endian_bytes(&self) -> Vec<u8> {
+        self.digits.iter().rev().copied().collect()
+    }
+
+    /// Magnitude of `self` (always non-negative)
+    pub fn abs(&self) -> Self {
+        Self {
+            digits: self.digits.clone(),
+            sign: false,
+        }
+    }
+
     /// Generate random number with `n` bytes
+    #[cfg(any(feature = "getrandom", all(feature = "std", unix)))]
     pub fn rand(n: usize) -> Self {
         if n == 0 {
             panic!("Can't create Bignum with 0 bytes. n has to be > 0");
         }
-        let mut f = std::fs::File::open("/dev/urandom").expect("Can't open file /dev/urandom");
         let mut buf = vec![0; n];
-        f.read_exact(&mut buf)
-            .expect("Can't read from file /dev/urandom");
-        Self {
+        rng::fill_bytes(&mut buf);
+
+        let mut bn = Self {
             digits: buf,
             sign: false,
-        }
+        };
+        bn.strip();
+        bn
     }
 }
 
@@ -627,35 +713,50 @@ impl PartialOrd for SignedBignum {
         true
     }
 
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Eq for SignedBignum {}
+
+impl core::hash::Hash for SignedBignum {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.sign.hash(state);
+        self.digits.hash(state);
+    }
+}
+
+impl Ord for SignedBignum {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
         let inverse = match (self.sign, other.sign) {
-            (true, false) => return Some(std::cmp::Ordering::Less),
-            (false, true) => return Some(std::cmp::Ordering::Greater),
+            (true, false) => return core::cmp::Ordering::Less,
+            (false, true) => return core::cmp::Ordering::Greater,
             (false, false) => false,
             (true, true) => true,
         };
 
         if self.digits.len() != other.digits.len() {
             match inverse {
-                false => return Some(self.digits.len().cmp(&other.digits.len())),
-                true => return Some(other.digits.len().cmp(&self.digits.len())),
+                false => return self.digits.len().cmp(&other.digits.len()),
+                true => return other.digits.len().cmp(&self.digits.len()),
             }
         }
 
         for (s, o) in self.digits.iter().rev().zip(other.digits.iter().rev()) {
             if s != o {
                 match inverse {
-                    false => return Some(s.cmp(o)),
-                    true => return Some(o.cmp(s)),
+                    false => return s.cmp(o),
+                    true => return o.cmp(s),
                 }
             }
         }
 
-        Some(std::cmp::Ordering::Equal)
+        core::cmp::Ordering::Equal
     }
 }
 
-impl std::ops::Shr<usize> for SignedBignum {
+impl core::ops::Shr<usize> for SignedBignum {
     type Output = Self;
 
     fn shr(mut self, rhs: usize) -> Self::Output {
@@ -666,26 +767,27 @@ impl std::ops::Shr<usize> for SignedBignum {
             self.digits.remove(0);
         }
 
-        if bit_shift == 0 {
-            self.strip();
-            return self;
-        }
-
-        let mut carry = 0;
-        for b in self.digits.iter_mut().rev() {
-            let tmp_carry = *b << (8 - bit_shift);
-            *b >>= bit_shift;
-            *b |= carry;
-            carry = tmp_carry;
+        if bit_shift != 0 {
+            let mut carry = 0;
+            for b in self.digits.iter_mut().rev() {
+                let tmp_carry = *b << (8 - bit_shift);
+                *b >>= bit_shift;
+                *b |= carry;
+                carry = tmp_carry;
+            }
         }
 
         self.strip();
+        // shifting out all bits of a negative value must not produce a negative zero
+        if self.is_zero() {
+            self.sign = false;
+        }
 
         self
     }
 }
 
-impl std::ops::Shl<usize> for SignedBignum {
+impl core::ops::Shl<usize> for SignedBignum {
     type Output = Self;
 
     fn shl(mut self, rhs: usize) -> Self::Output {
@@ -716,7 +818,7 @@ impl std::ops::Shl<usize> for SignedBignum {
     }
 }
 
-impl std::ops::Add for SignedBignum {
+impl core::ops::Add for SignedBignum {
     type Output = Self;
 
     fn add(self, rhs: Self) -> Self::Output {
@@ -724,7 +826,7 @@ impl std::ops::Add for SignedBignum {
     }
 }
 
-impl std::ops::Sub for SignedBignum {
+impl core::ops::Sub for SignedBignum {
     type Output = Self;
 
     fn sub(self, rhs: Self) -> Self::Output {
@@ -733,7 +835,7 @@ impl std::ops::Sub for SignedBignum {
 }
 
 // Long Multiplication (https://en.wikipedia.org/wiki/Multiplication_algorithm#Long_multiplication)
-impl std::ops::Mul for SignedBignum {
+impl core::ops::Mul for SignedBignum {
     type Output = Self;
 
     fn mul(self, rhs: Self) -> Self::Output {
@@ -741,7 +843,7 @@ impl std::ops::Mul for SignedBignum {
     }
 }
 
-impl std::ops::Div for SignedBignum {
+impl core::ops::Div for SignedBignum {
     type Output = Self;
 
     fn div(self, rhs: Self) -> Self::Output {
@@ -750,6 +852,40 @@ impl std::ops::Div for SignedBignum {
     }
 }
 
+impl core::ops::Neg for SignedBignum {
+    type Output = Self;
+
+    /// Flips the sign, zero always stays positive
+    fn neg(mut self) -> Self::Output {
+        self.sign = !self.sign && !self.is_zero();
+        self
+    }
+}
+
+impl core::fmt::Display for SignedBignum {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.pad_integral(
+            !self.sign || self.is_zero(),
+            "",
+            &UnsignedBignum::from_little_endian(&self.digits).to_decimal_string(),
+        )
+    }
+}
+
+impl core::fmt::LowerHex for SignedBignum {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let s = self.to_hex_string();
+        f.pad_integral(!self.sign || self.is_zero(), "0x", &s[2..])
+    }
+}
+
+impl core::fmt::UpperHex for SignedBignum {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let s = self.to_hex_string().to_uppercase();
+        f.pad_integral(!self.sign || self.is_zero(), "0x", &s[2..])
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -829,6 +965,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn rem_euclid() {
+        for (a, b) in get_test_cases() {
+            if b == 0 {
+                continue;
+            }
+
+            let big_a = SignedBignum::from(a);
+            let big_b = SignedBignum::from(b);
+
+            let r = big_a.rem_euclid(&big_b);
+            assert_eq!(r, SignedBignum::from(a.rem_euclid(b)));
+            assert!(!r.sign);
+        }
+
+        let r = SignedBignum::from(-7).rem_euclid(&SignedBignum::from(3));
+        assert_eq!(r, SignedBignum::from(2));
+    }
+
     #[test]
     fn pow() {
         let mut test_cases: Vec<(u128, u128)> = vec![(0, 0xa), (0xa, 0), (0, 0)];
@@ -899,6 +1054,32 @@ mod tests {
         }
     }
 
+    #[test]
+    fn shift_right_boundaries() {
+        // the magnitude is shifted, the sign is kept unless the result is zero
+        let test_cases: Vec<(i128, usize)> = vec![
+            (0x0100, 8),
+            (0x0100, 9),
+            (0x01ff, 8),
+            (0x0100, 1),
+            (0x00010000, 16),
+            (0x00010000, 17),
+            (-0x0100, 8),
+            (-0x0100, 9),
+            (-0x01, 1),
+            (-0xff, 8),
+            (-0x00010000, 24),
+        ];
+
+        for (a, b) in test_cases {
+            let res = SignedBignum::from(a.signum() * (a.abs() >> b));
+            let res_big = SignedBignum::from(a) >> b;
+
+            assert_eq!(res, res_big);
+            assert_eq!(res_big.digits.last() == Some(&0), res_big.is_zero());
+        }
+    }
+
     #[test]
     fn shift_left() {
         let base = 0xabcedef;
@@ -992,6 +1173,52 @@ mod tests {
         }
     }
 
+    #[test]
+    fn mpint() {
+        // RFC 4251 - Section 5
+        for (n, mpint) in [
+            (0i128, vec![0x00, 0x00, 0x00, 0x00]),
+            (
+                0x9a378f9b2e332a7,
+                vec![
+                    0x00, 0x00, 0x00, 0x08, 0x09, 0xa3, 0x78, 0xf9, 0xb2, 0xe3, 0x32, 0xa7,
+                ],
+            ),
+            (0x80, vec![0x00, 0x00, 0x00, 0x02, 0x00, 0x80]),
+            (-0x1234, vec![0x00, 0x00, 0x00, 0x02, 0xed, 0xcc]),
+            (
+                -0xdeadbeef,
+                vec![0x00, 0x00, 0x00, 0x05, 0xff, 0x21, 0x52, 0x41, 0x11],
+            ),
+            (-0x80, vec![0x00, 0x00, 0x00, 0x01, 0x80]),
+            (-0x81, vec![0x00, 0x00, 0x00, 0x02, 0xff, 0x7f]),
+            (-0x100, vec![0x00, 0x00, 0x00, 0x02, 0xff, 0x00]),
+            (0x7f, vec![0x00, 0x00, 0x00, 0x01, 0x7f]),
+            (-1, vec![0x00, 0x00, 0x00, 0x01, 0xff]),
+        ] {
+            let bn = SignedBignum::from(n);
+
+            assert_eq!(bn.to_mpint(), mpint);
+            assert_eq!(SignedBignum::from_mpint(&mpint).unwrap(), bn);
+        }
+    }
+
+    #[test]
+    fn mpint_roundtrip() {
+        for (a, _) in get_test_cases() {
+            let bn = SignedBignum::from(a);
+            assert_eq!(SignedBignum::from_mpint(&bn.to_mpint()).unwrap(), bn);
+        }
+    }
+
+    #[test]
+    fn mpint_invalid() {
+        assert!(SignedBignum::from_mpint(&[]).is_err());
+        assert!(SignedBignum::from_mpint(&[0x00, 0x00, 0x00]).is_err());
+        assert!(SignedBignum::from_mpint(&[0x00, 0x00, 0x00, 0x02, 0x01]).is_err());
+        assert!(SignedBignum::from_mpint(&[0x00, 0x00, 0x00, 0x00, 0x01]).is_err());
+    }
+
     #[test]
     fn from_hex_string() {
         for s in [
@@ -1006,4 +1233,95 @@ mod tests {
             assert_eq!(s, bn.to_hex_string());
         }
     }
+
+    #[test]
+    fn formatting() {
+        for n in [
+            0i128,
+            1,
+            -1,
+            10,
+            -10,
+            0xabcdef,
+            -0xabcdef,
+            i128::MAX,
+            i128::MIN + 1,
+        ] {
+            let big_n = SignedBignum::from(n);
+            let abs = n.unsigned_abs();
+            let sign = if n < 0 { "-" } else { "" };
+
+            assert_eq!(format!("{}", big_n), format!("{}", n));
+            assert_eq!(format!("{:>50}", big_n), format!("{:>50}", n));
+            assert_eq!(format!("{:+}", big_n), format!("{:+}", n));
+            assert_eq!(format!("{:x}", big_n), format!("{}{:x}", sign, abs));
+            assert_eq!(format!("{:#X}", big_n), format!("{}{:#X}", sign, abs));
+            assert_eq!(
+                format!("{:#012x}", big_n),
+                format!("{}{:#0w$x}", sign, abs, w = 12 - sign.len())
+            );
+        }
+    }
+
+    #[test]
+    fn neg_abs() {
+        for n in [0i128, 1, -1, 0xabcdef, -0xabcdef, i128::MAX, i128::MIN + 1] {
+            let big_n = SignedBignum::from(n);
+
+            assert_eq!(-big_n.clone(), SignedBignum::from(-n));
+            assert_eq!(-(-big_n.clone()), big_n);
+            assert_eq!(big_n.abs(), SignedBignum::from(n.abs()));
+            assert_eq!((-big_n.clone()).abs(), big_n.abs());
+        }
+
+        let zero = SignedBignum::new();
+        assert_eq!(-zero.clone(), zero);
+        assert!(!(-zero.clone()).sign);
+        assert!(!(-(-zero.clone())).sign);
+        assert!(!(-zero).abs().sign);
+    }
+
+    #[test]
+    fn from_min_values() {
+        let a = SignedBignum::from(i128::MIN);
+        assert_eq!(a.to_hex_string(), "0x80000000000000000000000000000000");
+        assert_eq!(a.to_string(), i128::MIN.to_string());
+        assert!(a < SignedBignum::from(i128::MIN + 1));
+
+        let a = SignedBignum::from(i32::MIN);
+        assert_eq!(a.to_hex_string(), "0x80000000");
+        assert_eq!(a.to_string(), i32::MIN.to_string());
+        assert_eq!(a, SignedBignum::from(i32::MIN as i128));
+    }
+
+    #[test]
+    fn pow_mod_modulus_one() {
+        for x in [0u128, 1, 2, 0xabcdef, u128::MAX] {
+            for e in [0u128, 1, 2, 65537] {
+                let res =
+                    SignedBignum::from(x).pow_mod(SignedBignum::from(e), &SignedBignum::from(1));
+                assert!(res.is_zero());
+            }
+        }
+    }
+
+    #[test]
+    fn hash_map_keys() {
+        use std::collections::HashMap;
+
+        let mut map = HashMap::new();
+        for a in -50..50i128 {
+            map.insert(SignedBignum::from(a << 64), a);
+        }
+
+        for a in -50..50i128 {
+            let b = SignedBignum::from(a) * SignedBignum::from(1i128 << 64);
+            assert_eq!(map.get(&b), Some(&a));
+        }
+
+        // zero built from a negative value is still the positive zero
+        let zero = SignedBignum::from(-1i128) + SignedBignum::from(1i128);
+        assert_eq!(zero, SignedBignum::new());
+        assert_eq!(map.get(&zero), Some(&0));
+    }
 }
@@ -1,4 +1,8 @@
-use std::io::Read;
+#[cfg(any(feature = "getrandom", all(feature = "std", unix)))]
+use crate::crypto::rng;
+use crate::math::bignum_fast::ParseError;
+use crate::math::utils;
+use alloc::{format, string::String, string::ToString, vec, vec::Vec};
 /// Internal storage in little endian
 ///
 /// 0xabcdef00 -> Bignum([0x00, 0xef, 0xcd, 0xab])
@@ -55,28 +59,61 @@ impl UnsignedBignum {
         format!("0x{}", res)
     }
 
-    pub fn try_from_hex_string(t: &str) -> Result<Self, std::num::ParseIntError> {
-        let s = t.trim_start_matches("0x");
+    pub fn try_from_hex_string(t: &str) -> Result<Self, ParseError> {
+        let bytes = utils::hex_to_le_bytes(t)?;
+        Ok(UnsignedBignum::from_little_endian(&bytes))
+    }
+
+    pub fn to_decimal_string(&self) -> String {
+        let mut res = vec![];
+        let mut n = self.clone();
 
-        let mut vec = vec![];
+        loop {
+            let r;
+            (n, r) = n.div_rem_small(10);
+            res.push(b'0' + r);
 
-        let len = s.len();
-        for i in 0..len / 2 {
-            let b = &s[len - (2 * i + 2)..len - 2 * i];
-            let b = u8::from_str_radix(b, 16)?;
-            vec.push(b);
+            if n.is_zero() {
+                break;
+            }
         }
 
-        if len % 2 != 0 {
-            let b = &s[0..1];
-            let b = u8::from_str_radix(b, 16)?;
-            vec.push(b);
+        res.reverse();
+        String::from_utf8(res).expect("decimal digits are valid utf-8")
+    }
+
+    /// Horner's method: multiply by 10 and add the next digit
+    pub fn from_decimal_string(s: &str) -> Result<Self, ParseError> {
+        if s.is_empty() {
+            return Err(ParseError::Empty);
         }
 
-        let mut b = UnsignedBignum::from_little_endian(&vec);
-        b.strip();
+        let ten = Self::from(10);
+        let mut res = Self::new();
 
-        Ok(b)
+        for c in s.chars() {
+            let d = c.to_digit(10).ok_or(ParseError::InvalidDigit)?;
+            res = res.mul_ref(&ten).add_ref(&Self::from(d as u128));
+        }
+
+        Ok(res)
+    }
+
+    /// Short division by a single byte
+    /// returns (quotient, remainder)
+    fn div_rem_small(&self, d: u8) -> (Self, u8) {
+        let mut digits = vec![0; self.digits.len()];
+
+        let mut r = 0u16;
+        for (i, b) in self.digits.iter().enumerate().rev() {
+            let cur = (r << 8) | *b as u16;
+            digits[i] = (cur / d as u16) as u8;
+            r = cur % d as u16;
+        }
+
+        let mut q = Self { digits };
+        q.strip();
+        (q, r as u8)
     }
 
     pub fn len(&self) -> usize {
@@ -167,7 +204,7 @@ impl UnsignedBignum {
     }
 
     pub fn is_even(&self) -> bool {
-        self.digits[0] % 2 == 0
+        self.digits[0].is_multiple_of(2)
     }
 
     /// Exponentiation by squaring (https://en.wikipedia.org/wiki/Exponentiation_by_squaring)
@@ -195,6 +232,27 @@ impl UnsignedBignum {
         x * y
     }
 
+    /// Integer square root (floor) via Newton's method (https://en.wikipedia.org/wiki/Integer_square_root#Algorithm_using_Newton's_method)
+    pub fn isqrt(&self) -> Self {
+        if self.is_zero() {
+            return Self::new();
+        }
+
+        // initial guess 2^ceil(bits / 2) is always >= sqrt(self)
+        let top = self.digits[self.digits.len() - 1];
+        let bits = (self.digits.len() - 1) * 8 + (8 - top.leading_zeros() as usize);
+        let mut x = Self::from(1) << bits.div_ceil(2);
+
+        loop {
+            let (q, _) = self.div_with_remainder(&x);
+            let y = x.add_ref(&q) >> 1;
+            if y >= x {
+                return x;
+            }
+            x = y;
+        }
+    }
+
     pub fn mul_ref(&self, other: &Self) -> Self {
         let p = self.digits.len();
         let q = other.digits.len();
@@ -221,6 +279,11 @@ impl UnsignedBignum {
     }
 
     pub fn pow_mod(self, exponent: Self, modulus: &Self) -> Self {
+        // every value is congruent to 0 mod 1
+        if *modulus == Self::from(1) {
+            return Self::new();
+        }
+
         let mut base = self;
         let mut exp = exponent;
 
@@ -271,19 +334,16 @@ impl UnsignedBignum {
             );
         }
 
-        let (long, short) = match self > rhs {
-            true => (self, rhs),
-            false => (rhs, self),
-        };
-        let mut vec = vec![0u8; long.len()];
+        // self >= rhs, so self has at least as many digits as rhs
+        let mut vec = vec![0u8; self.len()];
 
         let mut carry = 0;
         for (i, e) in vec.iter_mut().enumerate() {
-            let (mut sum, mut tmp_carry) = long.digits[i].overflowing_sub(carry);
+            let (mut sum, mut tmp_carry) = self.digits[i].overflowing_sub(carry);
             carry = tmp_carry as u8;
 
-            if i < short.len() {
-                (sum, tmp_carry) = sum.overflowing_sub(short.digits[i]);
+            if i < rhs.len() {
+                (sum, tmp_carry) = sum.overflowing_sub(rhs.digits[i]);
                 carry += tmp_carry as u8;
             }
 
@@ -297,15 +357,17 @@ impl UnsignedBignum {
     }
 
     /// Generate random number with `n` bytes
+    #[cfg(any(feature = "getrandom", all(feature = "std", unix)))]
     pub fn rand(n: usize) -> Self {
         if n == 0 {
             panic!("Can't create Bignum with 0 bytes. n has to be > 0");
         }
-        let mut f = std::fs::File::open("/dev/urandom").expect("Can't open file /dev/urandom");
         let mut buf = vec![0; n];
-        f.read_exact(&mut buf)
-            .expect("Can't read from file /dev/urandom");
-        Self { digits: buf }
+        rng::fill_bytes(&mut buf);
+
+        let mut bn = Self { digits: buf };
+        bn.strip();
+        bn
     }
 }
 
@@ -415,22 +477,36 @@ impl PartialOrd for UnsignedBignum {
         true
     }
 
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Eq for UnsignedBignum {}
+
+impl core::hash::Hash for UnsignedBignum {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.digits.hash(state);
+    }
+}
+
+impl Ord for UnsignedBignum {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
         if self.digits.len() != other.digits.len() {
-            return Some(self.digits.len().cmp(&other.digits.len()));
+            return self.digits.len().cmp(&other.digits.len());
         }
 
         for (s, o) in self.digits.iter().rev().zip(other.digits.iter().rev()) {
             if s != o {
-                return Some(s.cmp(o));
+                return s.cmp(o);
             }
         }
 
-        Some(std::cmp::Ordering::Equal)
+        core::cmp::Ordering::Equal
     }
 }
 
-impl std::ops::Shr<usize> for UnsignedBignum {
+impl core::ops::Shr<usize> for UnsignedBignum {
     type Output = Self;
 
     fn shr(mut self, rhs: usize) -> Self::Output {
@@ -460,7 +536,7 @@ impl std::ops::Shr<usize> for UnsignedBignum {
     }
 }
 
-impl std::ops::Shl<usize> for UnsignedBignum {
+impl core::ops::Shl<usize> for UnsignedBignum {
     type Output = Self;
 
     fn shl(mut self, rhs: usize) -> Self::Output {
@@ -491,7 +567,7 @@ impl std::ops::Shl<usize> for UnsignedBignum {
     }
 }
 
-impl std::ops::Add for UnsignedBignum {
+impl core::ops::Add for UnsignedBignum {
     type Output = Self;
 
     fn add(self, rhs: Self) -> Self::Output {
@@ -499,7 +575,7 @@ impl std::ops::Add for UnsignedBignum {
     }
 }
 
-impl std::ops::Sub for UnsignedBignum {
+impl core::ops::Sub for UnsignedBignum {
     type Output = Self;
 
     fn sub(self, rhs: Self) -> Self::Output {
@@ -508,7 +584,7 @@ impl std::ops::Sub for UnsignedBignum {
 }
 
 // Long Multiplication (https://en.wikipedia.org/wiki/Multiplication_algorithm#Long_multiplication)
-impl std::ops::Mul for UnsignedBignum {
+impl core::ops::Mul for UnsignedBignum {
     type Output = Self;
 
     fn mul(self, rhs: Self) -> Self::Output {
@@ -516,7 +592,7 @@ impl std::ops::Mul for UnsignedBignum {
     }
 }
 
-impl std::ops::Div for UnsignedBignum {
+impl core::ops::Div for UnsignedBignum {
     type Output = Self;
 
     fn div(self, rhs: Self) -> Self::Output {
@@ -525,6 +601,26 @@ impl std::ops::Div for UnsignedBignum {
     }
 }
 
+impl core::fmt::Display for UnsignedBignum {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.pad_integral(true, "", &self.to_decimal_string())
+    }
+}
+
+impl core::fmt::LowerHex for UnsignedBignum {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let s = self.to_hex_string();
+        f.pad_integral(true, "0x", &s[2..])
+    }
+}
+
+impl core::fmt::UpperHex for UnsignedBignum {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let s = self.to_hex_string().to_uppercase();
+        f.pad_integral(true, "0x", &s[2..])
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -571,6 +667,17 @@ mod tests {
         }
     }
 
+    #[test]
+    fn subtraction_equal() {
+        for a in [0u128, 1, 0xff, 0x100, 0xabcdef, u128::MAX] {
+            let res = UnsignedBignum::from(a).sub_ref(&UnsignedBignum::from(a));
+
+            assert_eq!(res.digits, vec![0]);
+            assert_eq!(res.len(), 1);
+            assert_eq!(res, UnsignedBignum::new());
+        }
+    }
+
     #[test]
     #[should_panic]
     fn subtraction_panic() {
@@ -609,11 +716,11 @@ mod tests {
             }
 
             let big_a = UnsignedBignum::from(a);
-            let big_b = UnsignedBignum::from(b as u128);
+            let big_b = UnsignedBignum::from(b);
 
             let (big_q, big_r) = UnsignedBignum::div_with_remainder(&big_a, &big_b);
-            let q = UnsignedBignum::from(a / b as u128);
-            let r = UnsignedBignum::from(a % b as u128);
+            let q = UnsignedBignum::from(a / b);
+            let r = UnsignedBignum::from(a % b);
 
             assert_eq!(big_q, q);
             assert_eq!(big_r, r);
@@ -797,4 +904,115 @@ mod tests {
             assert_eq!(s, bn.to_hex_string());
         }
     }
+
+    #[test]
+    fn isqrt() {
+        let mut test_cases: Vec<u128> = (0..1000).collect();
+        test_cases.extend((0..0xabcedef).step_by(300_000));
+        test_cases.extend([u64::MAX as u128, 1 << 100, (1 << 100) - 1, u128::MAX]);
+
+        for n in test_cases {
+            let big_n = UnsignedBignum::from(n);
+            let r = big_n.isqrt();
+
+            let r_plus_one = r.clone() + UnsignedBignum::from(1);
+            assert!(r.mul_ref(&r) <= big_n);
+            assert!(r_plus_one.mul_ref(&r_plus_one) > big_n);
+        }
+    }
+
+    #[test]
+    fn decimal_string() {
+        let mut test_cases: Vec<u128> = (0..1000).collect();
+        test_cases.extend((0..0xabcedef).step_by(300_000));
+        test_cases.extend([u64::MAX as u128, 1 << 100, u128::MAX]);
+
+        for n in test_cases {
+            let big_n = UnsignedBignum::from(n);
+
+            let s = big_n.to_decimal_string();
+            assert_eq!(s, n.to_string());
+            assert_eq!(UnsignedBignum::from_decimal_string(&s).unwrap(), big_n);
+        }
+
+        let s = "1234567890123456789012345678901234567890123456789012345678901234567890";
+        assert_eq!(
+            UnsignedBignum::from_decimal_string(s)
+                .unwrap()
+                .to_decimal_string(),
+            s
+        );
+        assert_eq!(
+            UnsignedBignum::from_decimal_string("000123").unwrap(),
+            UnsignedBignum::from(123)
+        );
+    }
+
+    #[test]
+    fn decimal_string_invalid() {
+        assert_eq!(
+            UnsignedBignum::from_decimal_string(""),
+            Err(ParseError::Empty)
+        );
+        for s in ["12a", "-1", "+1", "0x10", " 1", "1ä", "١"] {
+            assert_eq!(
+                UnsignedBignum::from_decimal_string(s),
+                Err(ParseError::InvalidDigit)
+            );
+        }
+    }
+
+    #[test]
+    fn formatting() {
+        for n in [0u128, 1, 9, 10, 0xabcdef, u64::MAX as u128, u128::MAX] {
+            let big_n = UnsignedBignum::from(n);
+
+            assert_eq!(format!("{}", big_n), format!("{}", n));
+            assert_eq!(format!("{:x}", big_n), format!("{:x}", n));
+            assert_eq!(format!("{:X}", big_n), format!("{:X}", n));
+            assert_eq!(format!("{:#x}", big_n), format!("{:#x}", n));
+            assert_eq!(format!("{:#X}", big_n), format!("{:#X}", n));
+            assert_eq!(format!("{:>40}", big_n), format!("{:>40}", n));
+            assert_eq!(format!("{:*<40x}", big_n), format!("{:*<40x}", n));
+            assert_eq!(format!("{:#020x}", big_n), format!("{:#020x}", n));
+            assert_eq!(format!("{:+}", big_n), format!("{:+}", n));
+        }
+    }
+
+    #[test]
+    fn rand() {
+        for n in 1..40 {
+            let a = UnsignedBignum::rand(n);
+            assert!(a.len() <= n);
+            assert!(a.is_zero() || a.digits[a.len() - 1] != 0);
+        }
+        assert_ne!(UnsignedBignum::rand(32), UnsignedBignum::rand(32));
+    }
+
+    #[test]
+    fn pow_mod_modulus_one() {
+        for x in [0u128, 1, 2, 0xabcdef, u128::MAX] {
+            for e in [0u128, 1, 2, 65537] {
+                let res = UnsignedBignum::from(x)
+                    .pow_mod(UnsignedBignum::from(e), &UnsignedBignum::from(1));
+                assert!(res.is_zero());
+            }
+        }
+    }
+
+    #[test]
+    fn hash_map_keys() {
+        use std::collections::HashMap;
+
+        let mut map = HashMap::new();
+        for a in 0..100u128 {
+            map.insert(UnsignedBignum::from(a << 64), a);
+        }
+
+        for a in 0..100u128 {
+            let from_bytes =
+                UnsignedBignum::from_big_endian(&[&[0; 4], &(a << 64).to_be_bytes()[..]].concat());
+            assert_eq!(map.get(&from_bytes), Some(&a));
+        }
+    }
 }
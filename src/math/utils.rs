@@ -1,4 +1,8 @@
+use crate::math::bignum_fast::{BignumFast, ParseError};
+use crate::math::signed_bignum::SignedBignum;
 use crate::math::unsigned_bignum::UnsignedBignum;
+use crate::util::hex;
+use alloc::vec::Vec;
 
 pub fn gcd(a: UnsignedBignum, b: UnsignedBignum) -> UnsignedBignum {
     let mut a = a;
@@ -15,6 +19,108 @@ pub fn gcd(a: UnsignedBignum, b: UnsignedBignum) -> UnsignedBignum {
     }
 }
 
+/// Little endian bytes of a hex string with an optional `0x` prefix, shared by the
+/// `try_from_hex_string` of all bignum types. An odd number of digits is read as if there was
+/// a leading `0`, so `"0xf"` and `"0x0f"` are the same value.
+///
+/// Never panics, empty input and anything but ASCII hex digits are rejected before slicing
+pub(crate) fn hex_to_le_bytes(s: &str) -> Result<Vec<u8>, ParseError> {
+    let s = s.strip_prefix("0x").unwrap_or(s);
+    if s.is_empty() {
+        return Err(ParseError::Empty);
+    }
+
+    let digits = s
+        .bytes()
+        .map(hex::nibble)
+        .collect::<Option<Vec<u8>>>()
+        .ok_or(ParseError::InvalidDigit)?;
+
+    Ok(digits
+        .rchunks(2)
+        .map(|pair| pair.iter().fold(0, |acc, d| (acc << 4) | d))
+        .collect())
+}
+
+/// Jacobi symbol (a/n) for odd `n` (https://en.wikipedia.org/wiki/Jacobi_symbol#Calculating_the_Jacobi_symbol)
+/// returns -1, 0 or 1. For prime `n` this is the Legendre symbol
+///
+/// # Panics
+/// if `n` is even
+pub fn jacobi<const N: usize>(a: &BignumFast<N>, n: &BignumFast<N>) -> i8 {
+    assert!(!n.is_even(), "Jacobi symbol is only defined for odd n");
+
+    let mut a = a.rem_ref(n);
+    let mut n = n.clone();
+    let mut t = 1;
+
+    while !a.is_zero() {
+        while a.is_even() {
+            a = a >> 1;
+            // (2/n) = -1 if n = 3, 5 (mod 8)
+            if matches!(n.rem_small(8), 3 | 5) {
+                t = -t;
+            }
+        }
+
+        // quadratic reciprocity
+        core::mem::swap(&mut a, &mut n);
+        if a.rem_small(4) == 3 && n.rem_small(4) == 3 {
+            t = -t;
+        }
+        a = a.rem_ref(&n);
+    }
+
+    match n.is_one() {
+        true => t,
+        false => 0,
+    }
+}
+
+/// Extended Euclidean algorithm (https://en.wikipedia.org/wiki/Extended_Euclidean_algorithm)
+/// returns `(g, x, y)` with `a * x + b * y = g = gcd(a, b)`. The Bézout coefficients `x` and `y`
+/// can be negative
+pub fn egcd(a: &SignedBignum, b: &SignedBignum) -> (SignedBignum, SignedBignum, SignedBignum) {
+    let (mut r0, mut r1) = (a.clone(), b.clone());
+    let (mut s0, mut s1) = (SignedBignum::from(1), SignedBignum::from(0));
+    let (mut t0, mut t1) = (SignedBignum::from(0), SignedBignum::from(1));
+
+    while !r1.is_zero() {
+        let (q, r) = r0.div_with_remainder(&r1);
+        (r0, r1) = (r1, r);
+
+        let s = s0.sub_ref(&q.mul_ref(&s1));
+        (s0, s1) = (s1, s);
+
+        let t = t0.sub_ref(&q.mul_ref(&t1));
+        (t0, t1) = (t1, t);
+    }
+
+    (r0, s0, t0)
+}
+
+/// Modular inverse of `a` mod `m` via `egcd`, the Bézout coefficient is normalized into
+/// `[0, m)` once here instead of every caller handling a negative sign.
+/// Returns `None` if `a` and `m` are not coprime or `m` is zero
+pub fn mod_inverse_signed<const N: usize>(
+    a: &BignumFast<N>,
+    m: &BignumFast<N>,
+) -> Option<BignumFast<N>> {
+    if m.is_zero() {
+        return None;
+    }
+
+    let signed_m = SignedBignum::from_big_endian(&m.to_big_endian_bytes());
+    let signed_a = SignedBignum::from_big_endian(&a.rem_ref(m).to_big_endian_bytes());
+
+    let (g, x, _) = egcd(&signed_a, &signed_m);
+    if g != SignedBignum::from(1) {
+        return None;
+    }
+
+    BignumFast::from_big_endian(&x.rem_euclid(&signed_m).to_big_endian_bytes())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -36,4 +142,97 @@ mod tests {
             assert_eq!(res, c);
         }
     }
+
+    #[test]
+    fn jacobi_test() {
+        let n: BignumFast<16> = BignumFast::from(21u32);
+        let expected = [
+            0, 1, -1, 0, 1, 1, 0, 0, -1, 0, -1, -1, 0, -1, 0, 0, 1, 1, 0, -1, 1,
+        ];
+        for (a, e) in expected.into_iter().enumerate() {
+            assert_eq!(jacobi(&BignumFast::from(a as u128), &n), e);
+        }
+
+        // Euler's criterion: (a/p) = a^((p-1)/2) mod p
+        for p in [3u128, 5, 7, 23, 101, 65537] {
+            let big_p: BignumFast<16> = BignumFast::from(p);
+            for a in (0..p).step_by((p as usize / 50).max(1)) {
+                let big_a = BignumFast::from(a);
+                let euler = big_a.clone().pow_mod(BignumFast::from((p - 1) / 2), &big_p);
+                let expected = match euler {
+                    e if e.is_zero() => 0,
+                    e if e == BignumFast::from(1u32) => 1,
+                    _ => -1,
+                };
+                assert_eq!(jacobi(&big_a, &big_p), expected);
+            }
+        }
+
+        // a >= n is reduced first
+        assert_eq!(jacobi(&BignumFast::<16>::from(21u32 + 2), &n), -1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn jacobi_even_n() {
+        let _ = jacobi(&BignumFast::<16>::from(3u32), &BignumFast::from(8u32));
+    }
+
+    #[test]
+    fn egcd_test() {
+        for (a, b) in [
+            (240, 46),
+            (46, 240),
+            (0, 5),
+            (5, 0),
+            (17, 17),
+            (0xaabbcc, 0xddeeff),
+        ] {
+            let (big_a, big_b) = (SignedBignum::from(a), SignedBignum::from(b));
+            let (g, x, y) = egcd(&big_a, &big_b);
+
+            assert_eq!(g, SignedBignum::from(gcd_u128(a, b)));
+            assert_eq!(big_a.mul_ref(&x).add_ref(&big_b.mul_ref(&y)), g);
+        }
+
+        // 240 * -9 + 46 * 47 = 2
+        let (g, x, y) = egcd(&SignedBignum::from(240), &SignedBignum::from(46));
+        assert_eq!((g, x, y), (2.into(), (-9).into(), 47.into()));
+    }
+
+    fn gcd_u128(a: u128, b: u128) -> u128 {
+        match b {
+            0 => a,
+            _ => gcd_u128(b, a % b),
+        }
+    }
+
+    #[test]
+    fn mod_inverse_signed_test() {
+        for m in 1..60u128 {
+            let big_m: BignumFast<16> = BignumFast::from(m);
+            for a in 0..2 * m {
+                let big_a = BignumFast::from(a);
+                let inv = mod_inverse_signed(&big_a, &big_m);
+
+                assert_eq!(inv, big_a.mod_inverse(&big_m));
+                if let Some(inv) = inv {
+                    assert!(inv < big_m);
+                    assert_eq!(big_a.mul_mod(&inv, &big_m), BignumFast::from(1 % m));
+                }
+            }
+        }
+
+        // 2^127 - 1 is prime
+        let p: BignumFast<32> = BignumFast::from(u128::MAX >> 1);
+        for _ in 0..10 {
+            let a = BignumFast::rand().rem_ref(&p);
+            assert_eq!(mod_inverse_signed(&a, &p), a.mod_inverse(&p));
+        }
+
+        assert_eq!(
+            mod_inverse_signed(&BignumFast::<16>::from(3u32), &BignumFast::zero()),
+            None
+        );
+    }
 }
@@ -0,0 +1,153 @@
+use alloc::{borrow::ToOwned, format, string::String, vec::Vec};
+
+/// Base64 alphabet (RFC 4648 - Section 4 and Section 5)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Alphabet {
+    /// `A-Z a-z 0-9 + /`
+    Standard,
+    /// `A-Z a-z 0-9 - _`, safe for URLs and file names
+    UrlSafe,
+}
+
+impl Alphabet {
+    fn symbols(self) -> &'static [u8; 64] {
+        match self {
+            Alphabet::Standard => {
+                b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/"
+            }
+            Alphabet::UrlSafe => {
+                b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_"
+            }
+        }
+    }
+
+    fn value(self, c: u8) -> Option<u8> {
+        self.symbols().iter().position(|s| *s == c).map(|v| v as u8)
+    }
+}
+
+/// Encode `bytes` as base64, `pad` appends `=` up to a multiple of 4 characters
+pub fn encode(bytes: &[u8], alphabet: Alphabet, pad: bool) -> String {
+    let symbols = alphabet.symbols();
+    let mut s = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+    for chunk in bytes.chunks(3) {
+        let mut block = [0; 3];
+        block[..chunk.len()].copy_from_slice(chunk);
+        let n = u32::from_be_bytes([0, block[0], block[1], block[2]]);
+
+        // 1, 2 or 3 bytes produce 2, 3 or 4 characters
+        for i in 0..=chunk.len() {
+            s.push(symbols[(n >> (18 - 6 * i)) as usize & 0x3f] as char);
+        }
+        if pad {
+            for _ in chunk.len()..3 {
+                s.push('=');
+            }
+        }
+    }
+
+    s
+}
+
+/// Decode base64 with or without padding
+///
+/// Rejects characters outside of `alphabet`, padding that does not complete the last
+/// group of 4 characters and non-zero bits in the unused part of the last character
+pub fn decode(s: &str, alphabet: Alphabet) -> Result<Vec<u8>, String> {
+    let data = s.trim_end_matches('=');
+    let padding = s.len() - data.len();
+
+    if padding > 0 && (!s.len().is_multiple_of(4) || padding > 2) {
+        return Err("invalid base64 padding".to_owned());
+    }
+    if data.len() % 4 == 1 {
+        return Err(format!("invalid base64 length {}", data.len()));
+    }
+
+    let mut bytes = Vec::with_capacity(data.len() / 4 * 3 + 2);
+    for chunk in data.as_bytes().chunks(4) {
+        let mut n = 0u32;
+        for (i, c) in chunk.iter().enumerate() {
+            let v = alphabet
+                .value(*c)
+                .ok_or_else(|| format!("invalid base64 character {:?}", *c as char))?;
+            n |= (v as u32) << (18 - 6 * i);
+        }
+
+        // 2, 3 or 4 characters carry 1, 2 or 3 bytes
+        let len = chunk.len() - 1;
+        if n & (0xffffff >> (8 * len)) != 0 {
+            return Err("non-zero trailing bits in base64 input".to_owned());
+        }
+        bytes.extend(&n.to_be_bytes()[1..=len]);
+    }
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rfc4648_test_vectors() {
+        // RFC 4648 - Section 10
+        let vectors = [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ];
+
+        for (plain, encoded) in vectors {
+            assert_eq!(encode(plain.as_bytes(), Alphabet::Standard, true), encoded);
+            assert_eq!(
+                decode(encoded, Alphabet::Standard).unwrap(),
+                plain.as_bytes()
+            );
+
+            let unpadded = encoded.trim_end_matches('=');
+            assert_eq!(encode(plain.as_bytes(), Alphabet::UrlSafe, false), unpadded);
+            assert_eq!(
+                decode(unpadded, Alphabet::UrlSafe).unwrap(),
+                plain.as_bytes()
+            );
+        }
+    }
+
+    #[test]
+    fn alphabets() {
+        let bytes = [0xfb, 0xff, 0xbf];
+        assert_eq!(encode(&bytes, Alphabet::Standard, true), "+/+/");
+        assert_eq!(encode(&bytes, Alphabet::UrlSafe, true), "-_-_");
+        assert_eq!(decode("+/+/", Alphabet::Standard).unwrap(), bytes);
+        assert_eq!(decode("-_-_", Alphabet::UrlSafe).unwrap(), bytes);
+
+        assert!(decode("-_-_", Alphabet::Standard).is_err());
+        assert!(decode("+/+/", Alphabet::UrlSafe).is_err());
+
+        let bytes: Vec<u8> = (0..=255).collect();
+        for alphabet in [Alphabet::Standard, Alphabet::UrlSafe] {
+            for pad in [true, false] {
+                assert_eq!(
+                    decode(&encode(&bytes, alphabet, pad), alphabet).unwrap(),
+                    bytes
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_input() {
+        for s in [
+            "Zg=", "Zg===", "Z===", "Zm8==", "Zm9v=", "=", "====", "Z", "Zm9vY", "Zm 9", "Zm9v\n",
+            "Zh==", "Zm9=", "Z=g=",
+        ] {
+            assert!(decode(s, Alphabet::Standard).is_err(), "{:?}", s);
+        }
+    }
+}
@@ -0,0 +1,53 @@
+/// Compare two byte slices in constant time with respect to their contents
+///
+/// Only a length mismatch returns early, otherwise the XOR of all byte pairs is
+/// accumulated and checked for zero once at the end
+pub fn bytes_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let mut diff = 0u8;
+    for (x, y) in a.iter().zip(b) {
+        diff |= x ^ y;
+    }
+
+    core::hint::black_box(diff) == 0
+}
+
+/// `0xff` if `b` is zero and `0x00` otherwise, without a branch
+pub(crate) fn zero_mask(b: u8) -> u8 {
+    ((b as u16).wrapping_sub(1) >> 8) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bytes_eq_test() {
+        let a: Vec<u8> = (0..=255).collect();
+
+        assert!(bytes_eq(&[], &[]));
+        assert!(bytes_eq(&a, &a.clone()));
+        assert!(!bytes_eq(&a, &a[..255]));
+        assert!(!bytes_eq(&[], &[0]));
+
+        // every single bit flip is detected
+        for i in 0..a.len() {
+            for bit in 0..8 {
+                let mut b = a.clone();
+                b[i] ^= 1 << bit;
+                assert!(!bytes_eq(&a, &b));
+            }
+        }
+    }
+
+    #[test]
+    fn zero_mask_test() {
+        assert_eq!(zero_mask(0), 0xff);
+        for b in 1..=255 {
+            assert_eq!(zero_mask(b), 0x00);
+        }
+    }
+}
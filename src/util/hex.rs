@@ -0,0 +1,80 @@
+use alloc::{format, string::String, vec::Vec};
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+/// Encode `bytes` as a lowercase hex string without prefix
+pub fn encode(bytes: &[u8]) -> String {
+    let mut s = String::with_capacity(2 * bytes.len());
+    for b in bytes {
+        s.push(HEX_DIGITS[(b >> 4) as usize] as char);
+        s.push(HEX_DIGITS[(b & 0xf) as usize] as char);
+    }
+    s
+}
+
+/// Value of a single hex digit, upper and lower case digits are accepted
+pub(crate) fn nibble(c: u8) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'a'..=b'f' => Some(c - b'a' + 10),
+        b'A'..=b'F' => Some(c - b'A' + 10),
+        _ => None,
+    }
+}
+
+/// Decode a hex string with an optional `0x` prefix, upper and lower case digits are accepted
+pub fn decode(s: &str) -> Result<Vec<u8>, String> {
+    let s = s.strip_prefix("0x").unwrap_or(s);
+    if !s.len().is_multiple_of(2) {
+        return Err(format!("hex string has odd length {}", s.len()));
+    }
+
+    s.as_bytes()
+        .chunks_exact(2)
+        .enumerate()
+        .map(|(i, pair)| {
+            let digit = |j: usize| {
+                nibble(pair[j]).ok_or_else(|| {
+                    format!(
+                        "invalid hex character {:?} at position {}",
+                        pair[j] as char,
+                        2 * i + j
+                    )
+                })
+            };
+            Ok((digit(0)? << 4) | digit(1)?)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        assert_eq!(decode(&encode(&bytes)).unwrap(), bytes);
+
+        assert_eq!(encode(&[]), "");
+        assert_eq!(encode(&[0x00, 0x0f, 0xa5, 0xff]), "000fa5ff");
+        assert!(decode("").unwrap().is_empty());
+        assert!(decode("0x").unwrap().is_empty());
+        assert_eq!(decode("000fa5ff").unwrap(), vec![0x00, 0x0f, 0xa5, 0xff]);
+        assert_eq!(decode("0x000FA5fF").unwrap(), vec![0x00, 0x0f, 0xa5, 0xff]);
+    }
+
+    #[test]
+    fn errors() {
+        assert!(decode("abc").is_err());
+        assert!(decode("0xabc").is_err());
+        assert!(decode("0g").is_err());
+        assert!(decode("0x0x").is_err());
+        assert!(decode(" 00").is_err());
+        assert!(decode("é").is_err());
+        assert_eq!(
+            decode("00zz").unwrap_err(),
+            "invalid hex character 'z' at position 2"
+        );
+    }
+}
@@ -0,0 +1,6 @@
+pub mod base64;
+pub mod ct;
+pub mod hex;
+mod secret;
+
+pub use secret::{zeroize, Secret};
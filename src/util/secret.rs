@@ -0,0 +1,77 @@
+use core::fmt;
+
+/// Wrapper for secret bytes like keys that zeroes them when dropped
+///
+/// `Debug` prints `Secret(...)` so the contents do not end up in logs by accident
+/// and `Display` is not implemented at all
+#[derive(Clone)]
+pub struct Secret<T: AsMut<[u8]>>(T);
+
+impl<T: AsMut<[u8]>> Secret<T> {
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    pub fn expose_secret(&self) -> &T {
+        &self.0
+    }
+
+    pub fn expose_secret_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: AsMut<[u8]>> From<T> for Secret<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: AsMut<[u8]>> fmt::Debug for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Secret(...)")
+    }
+}
+
+impl<T: AsMut<[u8]>> Drop for Secret<T> {
+    fn drop(&mut self) {
+        zeroize(self.0.as_mut());
+    }
+}
+
+/// Overwrite `bytes` with zeros using volatile writes, which the compiler can not optimize away
+pub fn zeroize(bytes: &mut [u8]) {
+    for b in bytes.iter_mut() {
+        // SAFETY: `b` is a valid, aligned reference into `bytes`
+        unsafe { core::ptr::write_volatile(b, 0) };
+    }
+    core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zeroed_on_drop() {
+        let mut key = [0xaa; 32];
+        {
+            let secret = Secret::new(&mut key[..]);
+            assert_eq!(secret.expose_secret()[0], 0xaa);
+        }
+        assert_eq!(key, [0; 32]);
+
+        let mut key = vec![0x55; 16];
+        drop(Secret::from(key.as_mut_slice()));
+        assert_eq!(key, vec![0; 16]);
+    }
+
+    #[test]
+    fn debug_is_redacted() {
+        let mut secret = Secret::new([0x41u8; 4]);
+        secret.expose_secret_mut()[0] = 0x42;
+
+        assert_eq!(format!("{:?}", secret), "Secret(...)");
+        assert_eq!(*secret.expose_secret(), [0x42, 0x41, 0x41, 0x41]);
+    }
+}
@@ -0,0 +1,132 @@
+use nikrypt::math::{
+    bignum_fast::{BignumFast, ParseError},
+    signed_bignum::SignedBignum,
+    unsigned_bignum::UnsignedBignum,
+};
+
+/// Hex strings and their values, shared by all bignum types
+const VALID: [(&str, u128); 14] = [
+    ("0x0", 0),
+    ("0", 0),
+    ("0x00", 0),
+    ("0xf", 0xf),
+    ("f", 0xf),
+    ("0x0f", 0xf),
+    ("0x100", 0x100),
+    ("0x00100", 0x100),
+    ("0xabc", 0xabc),
+    ("0xABCDEF", 0xabcdef),
+    ("0x10000000000000000", 1 << 64),
+    ("0x0000000000000000000000000000000001", 1),
+    (
+        "0x123456789abcdef0123456789abcdef",
+        0x123456789abcdef0123456789abcdef,
+    ),
+    ("0xffffffffffffffffffffffffffffffff", u128::MAX),
+];
+
+#[test]
+fn hex_strings_unsigned_bignum() {
+    for (s, v) in VALID {
+        assert_eq!(
+            UnsignedBignum::try_from_hex_string(s).unwrap(),
+            UnsignedBignum::from(v),
+            "{}",
+            s
+        );
+    }
+}
+
+#[test]
+fn hex_strings_signed_bignum() {
+    for (s, v) in VALID {
+        assert_eq!(
+            SignedBignum::try_from_hex_string(s).unwrap(),
+            SignedBignum::from(v),
+            "{}",
+            s
+        );
+    }
+}
+
+#[test]
+fn hex_strings_bignum_fast() {
+    for (s, v) in VALID {
+        let a: BignumFast<16> = BignumFast::try_from_hex_string(s).unwrap();
+        assert_eq!(a, BignumFast::from(v), "{}", s);
+        assert_eq!(a.to_hex_string(), BignumFast::<32>::from(v).to_hex_string());
+    }
+}
+
+#[test]
+fn hex_strings_same_output() {
+    for (s, _) in VALID {
+        let unsigned = UnsignedBignum::try_from_hex_string(s)
+            .unwrap()
+            .to_hex_string();
+        let signed = SignedBignum::try_from_hex_string(s)
+            .unwrap()
+            .to_hex_string();
+        let fast = BignumFast::<16>::try_from_hex_string(s)
+            .unwrap()
+            .to_hex_string();
+
+        assert_eq!(unsigned, signed);
+        assert_eq!(unsigned, fast);
+    }
+}
+
+/// Malformed hex strings, rejected by all bignum types without panicking
+const INVALID: [(&str, ParseError); 9] = [
+    ("", ParseError::Empty),
+    ("0x", ParseError::Empty),
+    ("0xzz", ParseError::InvalidDigit),
+    ("0x0x1", ParseError::InvalidDigit),
+    ("0x+f", ParseError::InvalidDigit),
+    ("-1", ParseError::InvalidDigit),
+    (" 0x1", ParseError::InvalidDigit),
+    ("0x1 ", ParseError::InvalidDigit),
+    ("0xé1", ParseError::InvalidDigit),
+];
+
+#[test]
+fn hex_strings_invalid() {
+    for (s, e) in INVALID {
+        assert_eq!(
+            UnsignedBignum::try_from_hex_string(s).unwrap_err(),
+            e,
+            "{}",
+            s
+        );
+        assert_eq!(
+            SignedBignum::try_from_hex_string(s).unwrap_err(),
+            e,
+            "{}",
+            s
+        );
+        assert_eq!(
+            BignumFast::<16>::try_from_hex_string(s).unwrap_err(),
+            e,
+            "{}",
+            s
+        );
+    }
+}
+
+#[test]
+fn hex_strings_too_large() {
+    // 33 digits don't fit into 16 bytes, leading zeros don't count
+    let s = "0x1ffffffffffffffffffffffffffffffff";
+    assert_eq!(
+        BignumFast::<16>::try_from_hex_string(s),
+        Err(ParseError::TooLarge)
+    );
+    assert!(BignumFast::<17>::try_from_hex_string(s).is_ok());
+    assert!(UnsignedBignum::try_from_hex_string(s).is_ok());
+
+    let padded = format!("0x{}1", "0".repeat(100));
+    assert_eq!(
+        BignumFast::<16>::try_from_hex_string(&padded),
+        Ok(BignumFast::from(1u32))
+    );
+}